@@ -13,65 +13,588 @@ export interface Module {
     path: string
 }
 
+export interface CancelToken {
+    readonly __cancelToken: unique symbol
+}
+
+export interface CompiledSignature {
+    readonly __compiledSignature: unique symbol
+}
+
+export interface SignatureCacheHandle {
+    readonly __signatureCache: unique symbol
+}
+
+export interface SignatureDatabaseHandle {
+    readonly __signatureDatabase: unique symbol
+}
+
+export interface SignatureFailure {
+    name: string
+    error: string
+}
+
+export interface SignatureResolutions {
+    resolved: Record<string, number>
+    /** Version of the entry each versioned signature was resolved with. */
+    versions: Record<string, string>
+    failed: SignatureFailure[]
+}
+
+export interface SignatureInfo {
+    length: number
+    wildcards: number
+}
+
+export type ReferenceKind = 'call' | 'jump' | 'ripRelative' | 'pointer32' | 'pointer64'
+
+export interface Reference {
+    address: number
+    kind: ReferenceKind
+}
+
+export interface StringReference extends Reference {
+    stringAddress: number
+    encoding: 'ascii' | 'utf16'
+}
+
+export interface ScanProgress {
+    bytesScanned: number
+    regionsScanned: number
+}
+
+export interface ScanOptions {
+    token?: CancelToken
+    timeout?: number
+    onProgress?: (progress: ScanProgress) => void
+}
+
+export type ValueType = 'bool' | 'i8' | 'u8' | 'i16' | 'u16' | 'i32' | 'u32' | 'i64' | 'u64' | 'f32' | 'f64'
+
+export type PointerSize = 4 | 8
+
+/** A value type, or `'pointer'` for the pointer width of the process. */
+export type TypeTag = ValueType | 'pointer'
+
+export type Endianness = 'little' | 'big'
+
+export type TextEncoding = 'utf8' | 'latin1' | 'utf16le'
+
+export interface StringReadOptions {
+    /** Defaults to `'utf8'`. */
+    encoding?: TextEncoding
+    /** Maximum length in code units (bytes, or 16-bit units for UTF-16), 4096 by default. */
+    maxLength?: number
+    /** Throw on invalid data instead of substituting U+FFFD. */
+    strict?: boolean
+}
+
+/** `'msvc'` is the MSVC release layout with small-string buffer, `'libstdc++'` the GCC C++11 ABI. */
+export type StringLayout = 'msvc' | 'libstdc++'
+
+export type VectorElement<T extends TypeTag | number> = T extends TypeTag ? TypedValue<T> : Buffer
+
+export interface StringWriteOptions {
+    /** Defaults to `'utf8'`. */
+    encoding?: TextEncoding
+    /** Append a NUL terminator, true by default. */
+    terminate?: boolean
+    /** Size of the destination buffer in bytes, terminator included. Longer strings throw and nothing is written. */
+    capacity?: number
+}
+
+/** 64-bit integers are BigInts so they stay exact; pointers are numbers like other addresses. */
+export type TypedValue<T extends TypeTag> = T extends 'i64' | 'u64' ? bigint : T extends 'bool' ? boolean : number
+
+/**
+ * A number, a BigInt or an expression such as `"osu!.exe"+1A2B3C -> +10 -> +44`. Numbers in
+ * expressions are hexadecimal, with or without `0x`; prefix `#` for decimal.
+ */
+export type Address = number | bigint | string
+
+export interface PointerRead<T> {
+    value: T
+    address: number
+}
+
+export interface ValueScanHandle {
+    readonly __valueScan: unique symbol
+}
+
+export interface MonoRuntimeHandle {
+    readonly __monoRuntime: unique symbol
+}
+
+/**
+ * Offsets into Mono's internal structures. The defaults match Unity's 64-bit `mono-2.0-bdwgc.dll`;
+ * the legacy `mono.dll` needs offsets passed explicitly, and 32-bit processes must pass every offset.
+ */
+export interface MonoOffsets {
+    domainAssemblies?: number
+    assemblyName?: number
+    assemblyImage?: number
+    imageClassCache?: number
+    hashTableSize?: number
+    hashTableTable?: number
+    classParent?: number
+    className?: number
+    classNamespace?: number
+    classVtableSize?: number
+    classFields?: number
+    classRuntimeInfo?: number
+    classFieldCount?: number
+    classNextCache?: number
+    fieldSize?: number
+    fieldType?: number
+    fieldName?: number
+    fieldOffset?: number
+    typeAttrs?: number
+    runtimeInfoVtables?: number
+    vtableData?: number
+}
+
+export interface MonoAssembly {
+    name: string
+    image: number
+}
+
+export interface MonoClass {
+    address: number
+    namespace: string
+    name: string
+    /** Address of the parent class, 0 for `System.Object`. */
+    parent: number
+}
+
+export interface MonoField {
+    name: string
+    /** Offset within an instance, or within the class's static data for static fields. */
+    offset: number
+    static: boolean
+    /** Address of a static field, absent until the class is initialized and for thread-static fields. */
+    address?: number
+}
+
+/** How float scans match: exactly, rounded to the operand's decimals, truncated, or within an epsilon. */
+export type FloatTolerance = 'exact' | 'rounded' | 'truncated' | number
+
+export interface FloatScanOptions {
+    tolerance?: FloatTolerance
+    /** Skip NaN and infinite values, defaults to true. */
+    finiteOnly?: boolean
+}
+
+export type ScanFilter = FloatScanOptions &
+    (
+        | { kind: 'exact'; value: number | boolean }
+        | { kind: 'range'; min: number; max: number }
+        | { kind: 'unknown' }
+        | { kind: 'changed' | 'unchanged' | 'increased' | 'decreased' }
+        | { kind: 'increasedBy' | 'decreasedBy'; value: number }
+    )
+
+/** A structure field, following the previous one unless `offset` is given. Without `kind`, a field with a value must equal it. */
+export type GroupField =
+    | (FloatScanOptions & {
+          type: ValueType | 'pointer'
+          offset?: number
+          kind?: 'exact' | 'range' | 'unknown'
+          value?: number | boolean
+          min?: number
+          max?: number
+      })
+    | { skip: number }
+
+export interface GroupScanOptions extends ScanOptions {
+    /** Alignment of candidate base addresses, defaults to 4. */
+    alignment?: number
+}
+
+export interface ValueScanResult {
+    address: number
+    value: number | bigint | boolean
+}
+
+export interface CompiledSchema {
+    readonly __compiledSchema: unique symbol
+}
+
+/** A struct field. Pointers with `fields` are followed, `count` makes the field a fixed array. */
+export interface SchemaField {
+    name: string
+    offset: number
+    type: ValueType | 'pointer' | 'struct'
+    count?: number
+    fields?: SchemaField[]
+}
+
+/** `[address, type]` reads a typed value, `[address, length]` reads a buffer. */
+/** Any buffer `read_into` can fill in place. DataView is not supported. */
+export type ReadTarget =
+    | Buffer
+    | Uint8Array
+    | Int8Array
+    | Uint16Array
+    | Int16Array
+    | Uint32Array
+    | Int32Array
+    | Float32Array
+    | Float64Array
+    | BigUint64Array
+    | BigInt64Array
+    | ArrayBuffer
+
+export type BatchReadEntry = [Address, ValueType] | [Address, number]
+
+export interface BatchReadResult {
+    /** One value per entry, `null` where the read failed. */
+    values: Array<number | bigint | boolean | Buffer | null>
+    /** Indices of the entries that could not be read. */
+    failed: number[]
+}
+
+/** `[address, type, value]` writes a typed value, `[address, buffer]` writes raw bytes. */
+export type BatchWriteEntry = [Address, ValueType, number | bigint | boolean] | [Address, Buffer]
+
+export interface PointerPath {
+    module: string
+    baseOffset: number
+    offsets: number[]
+    expression: string
+}
+
+export interface PointerScanOptions extends ScanOptions {
+    maxDepth?: number
+    maxOffset?: number
+    maxResults?: number
+    /** Pointers followed before the scan stops and returns the paths found so far. */
+    maxNodes?: number
+}
+
 export declare const open_process: (processName: string) => Process
 
 export declare const close_handle: (handle: number) => boolean
 
-export declare const sig_scan_sync: (handle: number, signature: string, baseAddress: number) => number | undefined
+export declare const create_cancel_token: () => CancelToken
 
-export declare const sig_scan: (handle: number, signature: string, baseAddress: number) => Promise<number | undefined>
+export declare const cancel_scan: (token: CancelToken) => void
+
+export declare const sig_scan_sync: (
+    handle: number,
+    signature: string | CompiledSignature,
+    baseAddress: number,
+) => number | undefined
+
+export declare const sig_scan: (
+    handle: number,
+    signature: string | CompiledSignature,
+    baseAddress: number,
+    options?: ScanOptions,
+) => Promise<number | undefined>
+
+export declare const group_scan: (
+    handle: number,
+    fields: GroupField[],
+    baseAddress: number,
+    options?: GroupScanOptions,
+) => Promise<number[]>
 
 export declare const sig_scan_module_sync: (
     handle: number,
     processId: number,
-    signature: string,
+    signature: string | CompiledSignature,
     moduleName: string,
-) => number | undefine
+) => number | undefined
 
 export declare const sig_scan_module: (
     handle: number,
     processId: number,
-    signature: string,
+    signature: string | CompiledSignature,
     moduleName: string,
-) => Promise<number | undefine>
+    options?: ScanOptions,
+) => Promise<number | undefined>
+
+export declare const sig_scan_reverse: (
+    handle: number,
+    processId: number,
+    signature: string | CompiledSignature,
+    address: number,
+) => number | undefined
+
+/**
+ * Guesses the start of the function containing `address` from alignment padding or, in 32-bit processes only,
+ * a `push ebp; mov ebp, esp` prologue.
+ */
+export declare const find_function_start: (handle: number, processId: number, address: number) => number | undefined
+
+export declare const find_references: (
+    handle: number,
+    processId: number,
+    target: number,
+    moduleName?: string | null,
+    options?: ScanOptions,
+) => Promise<Reference[]>
+
+export declare const find_string_references: (
+    handle: number,
+    processId: number,
+    text: string,
+    moduleName?: string | null,
+) => Promise<StringReference[]>
+
+export declare const sig_scan_module_cached_sync: (
+    handle: number,
+    processId: number,
+    signature: string | CompiledSignature,
+    moduleName: string,
+    cache: SignatureCacheHandle,
+) => number | undefined
+
+export declare const sig_scan_module_cached: (
+    handle: number,
+    processId: number,
+    signature: string | CompiledSignature,
+    moduleName: string,
+    cache: SignatureCacheHandle,
+) => Promise<number | undefined>
+
+export declare const signature_cache_load: (path: string) => SignatureCacheHandle
+
+export declare const signature_cache_save: (cache: SignatureCacheHandle, path: string) => void
 
-export declare const read_memory_buffer: (handle: number, address: number, size: number) => Buffer
+export declare const signature_database_load: (path: string) => SignatureDatabaseHandle
 
-export declare const write_memory_buffer: (handle: number, address: number, buffer: Buffer) => void
+export declare const resolve_signatures: (
+    handle: number,
+    processId: number,
+    database: SignatureDatabaseHandle,
+    version?: string,
+    cache?: SignatureCacheHandle,
+) => Promise<SignatureResolutions>
+
+export declare const compile_signature: (signature: string) => CompiledSignature
+
+export declare const signature_info: (signature: CompiledSignature) => SignatureInfo
+
+export declare const signature_scan_buffer: (signature: CompiledSignature, buffer: Buffer) => number | undefined
+
+export declare const evaluate_address: (handle: number, address: Address) => number
+
+export declare const compile_schema: (fields: SchemaField[]) => CompiledSchema
+
+export declare const read_struct: (
+    handle: number,
+    address: Address,
+    schema: SchemaField[] | CompiledSchema,
+    depth?: number,
+    pointerSize?: PointerSize,
+) => Record<string, unknown>
+
+export declare const read_memory_batch: (handle: number, entries: BatchReadEntry[]) => BatchReadResult
+
+export declare const read_memory_buffer: (handle: number, address: Address, size: number) => Buffer
+
+export declare const read_value: <T extends TypeTag>(
+    handle: number,
+    address: Address,
+    type: T,
+    endianness?: Endianness,
+) => TypedValue<T>
+
+/** Fills `buffer` in place and returns the number of bytes read. `offset` and `length` are in bytes. */
+export declare const read_into: (
+    handle: number,
+    address: Address,
+    buffer: ReadTarget,
+    offset?: number,
+    length?: number,
+) => number
+
+export declare const resolve_pointer_chain: (handle: number, addresses: number[], pointerSize?: PointerSize) => number
+
+export declare const read_memory_from_pointer: <T extends ValueType>(
+    handle: number,
+    addresses: number[],
+    type: T,
+    pointerSize?: PointerSize,
+) => PointerRead<TypedValue<T>>
+
+export declare const read_memory_buffer_from_pointer: (
+    handle: number,
+    addresses: number[],
+    size: number,
+    pointerSize?: PointerSize,
+) => PointerRead<Buffer>
+
+export declare const value_scan_first: (
+    handle: number,
+    type: ValueType,
+    filter: ScanFilter,
+    options?: ScanOptions,
+) => Promise<ValueScanHandle>
+
+export declare const value_scan_next: (
+    handle: number,
+    scan: ValueScanHandle,
+    filter: ScanFilter,
+    options?: ScanOptions,
+) => Promise<number>
+
+/** Throws while a `value_scan_next` on the same scan is running, as does `value_scan_results`. */
+export declare const value_scan_count: (scan: ValueScanHandle) => number
+
+export declare const value_scan_results: (scan: ValueScanHandle, offset: number, limit: number) => ValueScanResult[]
+
+export declare const pointer_scan: (
+    handle: number,
+    processId: number,
+    target: Address,
+    options?: PointerScanOptions,
+) => Promise<PointerPath[]>
+
+export declare const pointer_rescan: (
+    handle: number,
+    processId: number,
+    paths: PointerPath[],
+    target: Address,
+) => Promise<PointerPath[]>
+
+export declare const pointer_paths_save: (path: string, paths: PointerPath[]) => void
+
+export declare const pointer_paths_load: (path: string) => PointerPath[]
+
+/**
+ * Writes every entry or none. Throws an error with `index` and `address` of the entry that failed,
+ * and `restored` when earlier writes had to be rolled back.
+ */
+export declare const write_memory_batch: (handle: number, entries: BatchWriteEntry[]) => void
+
+export declare const write_memory_buffer: (handle: number, address: Address, buffer: Buffer) => void
+
+export declare const write_value: <T extends TypeTag>(
+    handle: number,
+    address: Address,
+    type: T,
+    value: TypedValue<T> | number,
+    endianness?: Endianness,
+) => void
 
 export declare const alloc_memory: (handle: number, size: number) => number
 
-export declare const read_memory_u32: (handle: number, address: number) => number
+export declare const read_memory_u32: (handle: number, address: Address) => number
+
+export declare const write_memory_u32: (handle: number, address: Address, value: number) => void
+
+export declare const read_memory_u64: (handle: number, address: Address) => bigint
 
-export declare const write_memory_u32: (handle: number, address: number, value: number) => void
+/** Loses precision above 2^53; `read_memory_u64` is exact. */
+export declare const read_memory_u64_lossy: (handle: number, address: Address) => number
 
-export declare const read_memory_u64: (handle: number, address: number) => number
+/** Numbers must be safe integers in range; anything else throws a RangeError. */
+export declare const write_memory_u64: (handle: number, address: Address, value: bigint | number) => void
 
-export declare const write_memory_u64: (handle: number, address: number, value: number) => void
+export declare const read_memory_i32: (handle: number, address: Address) => number
 
-export declare const read_memory_i32: (handle: number, address: number) => number
+export declare const write_memory_i32: (handle: number, address: Address, value: number) => void
 
-export declare const write_memory_i32: (handle: number, address: number, value: number) => void
+export declare const read_memory_i64: (handle: number, address: Address) => bigint
 
-export declare const read_memory_i64: (handle: number, address: number) => number
+/** Loses precision above 2^53; `read_memory_i64` is exact. */
+export declare const read_memory_i64_lossy: (handle: number, address: Address) => number
 
-export declare const write_memory_i64: (handle: number, address: number, value: number) => void
+/** Numbers must be safe integers in range; anything else throws a RangeError. */
+export declare const write_memory_i64: (handle: number, address: Address, value: bigint | number) => void
 
-export declare const read_memory_f32: (handle: number, address: number) => number
+export declare const read_memory_f32: (handle: number, address: Address) => number
 
-export declare const write_memory_f32: (handle: number, address: number, value: number) => void
+export declare const write_memory_f32: (handle: number, address: Address, value: number) => void
 
-export declare const read_memory_f64: (handle: number, address: number) => number
+export declare const read_memory_f64: (handle: number, address: Address) => number
 
-export declare const write_memory_f64: (handle: number, address: number, value: number) => void
+export declare const write_memory_f64: (handle: number, address: Address, value: number) => void
 
-export declare const read_memory_bool: (handle: number, address: number) => boolean
+export declare const read_memory_bool: (handle: number, address: Address) => boolean
 
-export declare const write_memory_bool: (handle: number, address: number, value: boolean) => void
+export declare const write_memory_bool: (handle: number, address: Address, value: boolean) => void
 
-export declare const read_memory_string: (handle: number, address: number) => string
+export declare const read_memory_string: (handle: number, address: Address, options?: StringReadOptions) => string
+
+export declare const write_memory_string: (
+    handle: number,
+    address: Address,
+    value: string,
+    options?: StringWriteOptions,
+) => void
+
+/** `encoding` is `'utf8'` for `std::string` and `'utf16le'` for `std::wstring`. */
+export declare const read_std_string: (
+    handle: number,
+    address: Address,
+    layout: StringLayout,
+    encoding?: TextEncoding,
+    pointerSize?: PointerSize,
+) => string
+
+/** Elements are decoded by type, or returned as Buffers of the given byte length. */
+export declare const read_vector: <T extends TypeTag | number>(
+    handle: number,
+    address: Address,
+    element: T,
+    pointerSize?: PointerSize,
+) => VectorElement<T>[]
+
+/** Reads a string preceded by its length in code units, stored in `prefixSize` bytes. */
+export declare const read_pascal_string: (
+    handle: number,
+    address: Address,
+    prefixSize: 1 | 2 | 4,
+    encoding?: TextEncoding,
+) => string
+
+/** Reads a .NET `System.String` from an object pointer. */
+export declare const read_clr_string: (handle: number, object: Address, pointerSize?: PointerSize) => string
+
+/** Reads the elements of a single-dimensional .NET array, `T[]`. Object references are `'pointer'`. */
+export declare const read_clr_array: <T extends TypeTag | number>(
+    handle: number,
+    object: Address,
+    element: T,
+    pointerSize?: PointerSize,
+) => VectorElement<T>[]
+
+/** Reads the live elements of a .NET `List<T>`. */
+export declare const read_clr_list: <T extends TypeTag | number>(
+    handle: number,
+    object: Address,
+    element: T,
+    pointerSize?: PointerSize,
+) => VectorElement<T>[]
+
+/** Reads the entries of a .NET `Dictionary<K,V>`. Reference keys and values must be `'pointer'` as they change the entry layout. */
+export declare const read_clr_dictionary: <K extends TypeTag | number, V extends TypeTag | number>(
+    handle: number,
+    object: Address,
+    key: K,
+    value: V,
+    pointerSize?: PointerSize,
+) => [VectorElement<K>, VectorElement<V>][]
+
+/** Attaches to the Mono runtime loaded in the process, throwing if there is none. */
+export declare const mono_open: (handle: number, processId: number, offsets?: MonoOffsets) => MonoRuntimeHandle
+
+export declare const mono_assemblies: (handle: number, runtime: MonoRuntimeHandle) => MonoAssembly[]
+
+export declare const mono_classes: (handle: number, runtime: MonoRuntimeHandle, image: Address) => MonoClass[]
+
+export declare const mono_find_class: (
+    handle: number,
+    runtime: MonoRuntimeHandle,
+    namespace: string,
+    name: string,
+) => MonoClass | undefined
 
-export declare const write_memory_string: (handle: number, address: number, value: string) => void
+/** Lists the fields a class declares, without inherited ones. Constants are skipped. */
+export declare const mono_class_fields: (handle: number, runtime: MonoRuntimeHandle, klass: Address) => MonoField[]
 
 export declare const get_process_path: (handle: number) => string
 
@@ -1,24 +1,108 @@
 /// <reference types="node" />
 import {
+    Address,
     alloc_memory,
+    BatchReadEntry,
+    BatchReadResult,
+    BatchWriteEntry,
+    cancel_scan,
     close_handle,
+    compile_schema,
+    compile_signature,
+    CompiledSchema,
+    CompiledSignature,
+    create_cancel_token,
+    Endianness,
+    evaluate_address,
+    find_function_start,
+    find_references,
+    find_string_references,
     get_process_modules,
+    group_scan,
+    GroupField,
     get_process_path,
     Module,
+    mono_assemblies,
+    mono_class_fields,
+    mono_classes,
+    mono_find_class,
+    mono_open,
+    MonoAssembly,
+    MonoClass,
+    MonoField,
+    MonoOffsets,
+    MonoRuntimeHandle,
     open_process,
+    pointer_paths_load,
+    pointer_paths_save,
+    pointer_rescan,
+    pointer_scan,
+    PointerPath,
+    PointerRead,
+    PointerSize,
+    read_clr_array,
+    read_clr_dictionary,
+    read_clr_list,
+    read_clr_string,
+    read_into,
+    read_memory_batch,
     read_memory_bool,
     read_memory_buffer,
+    read_memory_buffer_from_pointer,
     read_memory_f32,
     read_memory_f64,
+    read_memory_from_pointer,
     read_memory_i32,
     read_memory_i64,
+    read_memory_i64_lossy,
     read_memory_string,
     read_memory_u32,
     read_memory_u64,
+    read_memory_u64_lossy,
+    read_pascal_string,
+    read_std_string,
+    read_struct,
+    read_value,
+    read_vector,
+    ReadTarget,
+    Reference,
+    resolve_pointer_chain,
+    resolve_signatures,
+    ScanOptions,
+    ScanProgress,
     sig_scan,
     sig_scan_module,
+    sig_scan_module_cached,
+    sig_scan_module_cached_sync,
     sig_scan_module_sync,
+    sig_scan_reverse,
     sig_scan_sync,
+    signature_cache_load,
+    signature_cache_save,
+    SchemaField,
+    SignatureCacheHandle,
+    signature_database_load,
+    SignatureDatabaseHandle,
+    SignatureResolutions,
+    signature_info,
+    signature_scan_buffer,
+    StringLayout,
+    StringReadOptions,
+    StringReference,
+    StringWriteOptions,
+    TextEncoding,
+    TypedValue,
+    TypeTag,
+    value_scan_count,
+    value_scan_first,
+    value_scan_next,
+    value_scan_results,
+    ScanFilter,
+    ValueScanHandle,
+    ValueScanResult,
+    ValueType,
+    VectorElement,
+    write_memory_batch,
     write_memory_bool,
     write_memory_buffer,
     write_memory_f32,
@@ -28,8 +112,137 @@ import {
     write_memory_string,
     write_memory_u32,
     write_memory_u64,
+    write_value,
 } from './core'
 
+export interface SigScanOptions {
+    signal?: AbortSignal
+    timeout?: number
+    onProgress?: (progress: ScanProgress) => void
+}
+
+export interface GroupScanOptions extends SigScanOptions {
+    alignment?: number
+}
+
+export interface PointerScanOptions extends SigScanOptions {
+    maxDepth?: number
+    maxOffset?: number
+    maxResults?: number
+    /** Pointers followed before the scan stops and returns the paths found so far. */
+    maxNodes?: number
+}
+
+const withScanOptions = <T>(options: SigScanOptions, scan: (options: ScanOptions) => Promise<T>): Promise<T> => {
+    const { signal, timeout, onProgress } = options
+    if (!signal) {
+        return scan({ timeout, onProgress })
+    }
+    if (signal.aborted) {
+        return Promise.reject(new Error('Scan cancelled'))
+    }
+    const token = create_cancel_token()
+    const onAbort = () => cancel_scan(token)
+    signal.addEventListener('abort', onAbort, { once: true })
+    return scan({ token, timeout, onProgress }).finally(() => signal.removeEventListener('abort', onAbort))
+}
+
+export class Signature {
+    public readonly compiled: CompiledSignature
+    public readonly length: number
+    public readonly wildcards: number
+
+    constructor(pattern: string) {
+        this.compiled = compile_signature(pattern)
+        const info = signature_info(this.compiled)
+        this.length = info.length
+        this.wildcards = info.wildcards
+    }
+
+    scan(buffer: Buffer): number | undefined {
+        return signature_scan_buffer(this.compiled, buffer)
+    }
+}
+
+export class Schema {
+    public readonly compiled: CompiledSchema
+
+    constructor(public readonly fields: SchemaField[]) {
+        this.compiled = compile_schema(fields)
+    }
+}
+
+export class SignatureCache {
+    public readonly handle: SignatureCacheHandle
+
+    constructor(public readonly path: string) {
+        this.handle = signature_cache_load(path)
+    }
+
+    save(): void {
+        signature_cache_save(this.handle, this.path)
+    }
+}
+
+export class SignatureDatabase {
+    public readonly handle: SignatureDatabaseHandle
+
+    constructor(public readonly path: string) {
+        this.handle = signature_database_load(path)
+    }
+}
+
+export const savePointerPaths = (path: string, paths: PointerPath[]): void => pointer_paths_save(path, paths)
+
+export const loadPointerPaths = (path: string): PointerPath[] => pointer_paths_load(path)
+
+export class ValueScan {
+    constructor(private readonly process: Process, public readonly handle: ValueScanHandle) {}
+
+    get count(): number {
+        return value_scan_count(this.handle)
+    }
+
+    next(filter: ScanFilter, options: SigScanOptions = {}): Promise<number> {
+        return withScanOptions(options, (scanOptions) =>
+            value_scan_next(this.process.handle, this.handle, filter, scanOptions),
+        )
+    }
+
+    results(offset: number = 0, limit: number = 100): ValueScanResult[] {
+        return value_scan_results(this.handle, offset, limit)
+    }
+}
+
+export class MonoRuntime {
+    constructor(private readonly process: Process, public readonly handle: MonoRuntimeHandle) {}
+
+    assemblies(): MonoAssembly[] {
+        return mono_assemblies(this.process.handle, this.handle)
+    }
+
+    classes(image: Address): MonoClass[] {
+        return mono_classes(this.process.handle, this.handle, image)
+    }
+
+    findClass(namespace: string, name: string): MonoClass | undefined {
+        return mono_find_class(this.process.handle, this.handle, namespace, name)
+    }
+
+    fields(klass: Address): MonoField[] {
+        return mono_class_fields(this.process.handle, this.handle, klass)
+    }
+
+    /** Finds a field declared by the class `namespace.name`. */
+    findField(namespace: string, name: string, field: string): MonoField | undefined {
+        const klass = this.findClass(namespace, name)
+        return klass && this.fields(klass.address).find((candidate) => candidate.name === field)
+    }
+}
+
+const compiled = (signature: string | Signature): string | CompiledSignature =>
+    signature instanceof Signature ? signature.compiled : signature
+
 export class Process {
     public id: number
     public name: string
@@ -46,96 +259,279 @@ export class Process {
         return close_handle(this.handle)
     }
 
-    sigScanSync(signature: string, baseAddress: number = 0): number | undefined {
-        return sig_scan_sync(this.handle, signature, baseAddress)
+    sigScanSync(signature: string | Signature, baseAddress: number = 0): number | undefined {
+        return sig_scan_sync(this.handle, compiled(signature), baseAddress)
+    }
+
+    sigScan(
+        signature: string | Signature,
+        baseAddress: number = 0,
+        options: SigScanOptions = {},
+    ): Promise<number | undefined> {
+        return withScanOptions(options, (scanOptions) =>
+            sig_scan(this.handle, compiled(signature), baseAddress, scanOptions),
+        )
     }
 
-    sigScan(signature: string, baseAddress: number = 0): Promise<number | undefined> {
-        return sig_scan(this.handle, signature, baseAddress)
+    groupScan(fields: GroupField[], baseAddress: number = 0, options: GroupScanOptions = {}): Promise<number[]> {
+        const { alignment } = options
+        return withScanOptions(options, (scanOptions) =>
+            group_scan(this.handle, fields, baseAddress, { ...scanOptions, alignment }),
+        )
     }
 
-    sigScanModuleSync(signature: string, moduleName: string): number | undefined {
-        return sig_scan_module_sync(this.handle, this.id, signature, moduleName)
+    sigScanModuleSync(signature: string | Signature, moduleName: string): number | undefined {
+        return sig_scan_module_sync(this.handle, this.id, compiled(signature), moduleName)
     }
 
-    sigScanModule(signature: string, moduleName: string): Promise<number | undefined> {
-        return sig_scan_module(this.handle, this.id, signature, moduleName)
+    sigScanModule(
+        signature: string | Signature,
+        moduleName: string,
+        options: SigScanOptions = {},
+    ): Promise<number | undefined> {
+        return withScanOptions(options, (scanOptions) =>
+            sig_scan_module(this.handle, this.id, compiled(signature), moduleName, scanOptions),
+        )
     }
 
-    readMemoryBuffer(address: number, size: number): Buffer {
+    sigScanReverse(signature: string | Signature, address: number): number | undefined {
+        return sig_scan_reverse(this.handle, this.id, compiled(signature), address)
+    }
+
+    findFunctionStart(address: number): number | undefined {
+        return find_function_start(this.handle, this.id, address)
+    }
+
+    findReferences(target: number, moduleName?: string, options: SigScanOptions = {}): Promise<Reference[]> {
+        return withScanOptions(options, (scanOptions) =>
+            find_references(this.handle, this.id, target, moduleName, scanOptions),
+        )
+    }
+
+    findStringReferences(text: string, moduleName?: string): Promise<StringReference[]> {
+        return find_string_references(this.handle, this.id, text, moduleName)
+    }
+
+    sigScanModuleCachedSync(signature: string | Signature, moduleName: string, cache: SignatureCache): number | undefined {
+        return sig_scan_module_cached_sync(this.handle, this.id, compiled(signature), moduleName, cache.handle)
+    }
+
+    sigScanModuleCached(
+        signature: string | Signature,
+        moduleName: string,
+        cache: SignatureCache,
+    ): Promise<number | undefined> {
+        return sig_scan_module_cached(this.handle, this.id, compiled(signature), moduleName, cache.handle)
+    }
+
+    resolveSignatures(
+        database: SignatureDatabase,
+        version?: string,
+        cache?: SignatureCache,
+    ): Promise<SignatureResolutions> {
+        return resolve_signatures(this.handle, this.id, database.handle, version, cache?.handle)
+    }
+
+    evaluateAddress(expression: Address): number {
+        return evaluate_address(this.handle, expression)
+    }
+
+    readStruct<T = Record<string, unknown>>(
+        address: Address,
+        schema: Schema | SchemaField[],
+        depth: number = 1,
+        pointerSize?: PointerSize,
+    ): T {
+        const compiledSchema = schema instanceof Schema ? schema.compiled : schema
+        return read_struct(this.handle, address, compiledSchema, depth, pointerSize) as T
+    }
+
+    readMemoryBatch(entries: BatchReadEntry[]): BatchReadResult {
+        return read_memory_batch(this.handle, entries)
+    }
+
+    readMemoryBuffer(address: Address, size: number): Buffer {
         return read_memory_buffer(this.handle, address, size)
     }
 
-    writeMemoryBuffer(address: number, buffer: Buffer): void {
+    readInto(address: Address, buffer: ReadTarget, offset?: number, length?: number): number {
+        return read_into(this.handle, address, buffer, offset, length)
+    }
+
+    readValue<T extends TypeTag>(address: Address, type: T, endianness?: Endianness): TypedValue<T> {
+        return read_value(this.handle, address, type, endianness)
+    }
+
+    resolvePointerChain(addresses: number[], pointerSize?: PointerSize): number {
+        return resolve_pointer_chain(this.handle, addresses, pointerSize)
+    }
+
+    readMemoryFromPointer<T extends ValueType>(
+        addresses: number[],
+        type: T,
+        pointerSize?: PointerSize,
+    ): PointerRead<TypedValue<T>> {
+        return read_memory_from_pointer(this.handle, addresses, type, pointerSize)
+    }
+
+    readMemoryBufferFromPointer(addresses: number[], size: number, pointerSize?: PointerSize): PointerRead<Buffer> {
+        return read_memory_buffer_from_pointer(this.handle, addresses, size, pointerSize)
+    }
+
+    valueScan(type: ValueType, filter: ScanFilter, options: SigScanOptions = {}): Promise<ValueScan> {
+        return withScanOptions(options, (scanOptions) => value_scan_first(this.handle, type, filter, scanOptions)).then(
+            (handle) => new ValueScan(this, handle),
+        )
+    }
+
+    pointerScan(target: Address, options: PointerScanOptions = {}): Promise<PointerPath[]> {
+        const { maxDepth, maxOffset, maxResults, maxNodes } = options
+        return withScanOptions(options, (scanOptions) =>
+            pointer_scan(this.handle, this.id, target, {
+                ...scanOptions,
+                maxDepth,
+                maxOffset,
+                maxResults,
+                maxNodes,
+            }),
+        )
+    }
+
+    pointerRescan(paths: PointerPath[], target: Address): Promise<PointerPath[]> {
+        return pointer_rescan(this.handle, this.id, paths, target)
+    }
+
+    writeMemoryBatch(entries: BatchWriteEntry[]): void {
+        write_memory_batch(this.handle, entries)
+    }
+
+    writeMemoryBuffer(address: Address, buffer: Buffer): void {
         write_memory_buffer(this.handle, address, buffer)
     }
 
+    writeValue<T extends TypeTag>(
+        address: Address,
+        type: T,
+        value: TypedValue<T> | number,
+        endianness?: Endianness,
+    ): void {
+        write_value(this.handle, address, type, value, endianness)
+    }
+
     allocMemory(size: number): number {
         return alloc_memory(this.handle, size)
     }
 
-    readMemoryU32(address: number): number {
+    readMemoryU32(address: Address): number {
         return read_memory_u32(this.handle, address)
     }
 
-    writeMemoryU32(address: number, value: number): void {
+    writeMemoryU32(address: Address, value: number): void {
         write_memory_u32(this.handle, address, value)
     }
 
-    readMemoryU64(address: number): number {
+    readMemoryU64(address: Address): bigint {
         return read_memory_u64(this.handle, address)
     }
 
-    writeMemoryU64(address: number, value: number): void {
+    /** Loses precision above 2^53; use `readMemoryU64` for exact values. */
+    readMemoryU64Lossy(address: Address): number {
+        return read_memory_u64_lossy(this.handle, address)
+    }
+
+    writeMemoryU64(address: Address, value: bigint | number): void {
         write_memory_u64(this.handle, address, value)
     }
 
-    readMemoryI32(address: number): number {
+    readMemoryI32(address: Address): number {
         return read_memory_i32(this.handle, address)
     }
 
-    writeMemoryI32(address: number, value: number): void {
+    writeMemoryI32(address: Address, value: number): void {
         write_memory_i32(this.handle, address, value)
     }
 
-    readMemoryI64(address: number): number {
+    readMemoryI64(address: Address): bigint {
         return read_memory_i64(this.handle, address)
     }
 
-    writeMemoryI64(address: number, value: number): void {
+    /** Loses precision above 2^53; use `readMemoryI64` for exact values. */
+    readMemoryI64Lossy(address: Address): number {
+        return read_memory_i64_lossy(this.handle, address)
+    }
+
+    writeMemoryI64(address: Address, value: bigint | number): void {
         write_memory_i64(this.handle, address, value)
     }
 
-    readMemoryF32(address: number): number {
+    readMemoryF32(address: Address): number {
         return read_memory_f32(this.handle, address)
     }
 
-    writeMemoryF32(address: number, value: number): void {
+    writeMemoryF32(address: Address, value: number): void {
         write_memory_f32(this.handle, address, value)
     }
 
-    readMemoryF64(address: number): number {
+    readMemoryF64(address: Address): number {
         return read_memory_f64(this.handle, address)
     }
 
-    writeMemoryF64(address: number, value: number): void {
+    writeMemoryF64(address: Address, value: number): void {
         write_memory_f64(this.handle, address, value)
     }
 
-    readMemoryBool(address: number): boolean {
+    readMemoryBool(address: Address): boolean {
         return read_memory_bool(this.handle, address)
     }
 
-    writeMemoryBool(address: number, value: boolean): void {
+    writeMemoryBool(address: Address, value: boolean): void {
         write_memory_bool(this.handle, address, value)
     }
 
-    readMemoryString(address: number): string {
-        return read_memory_string(this.handle, address)
+    readMemoryString(address: Address, options?: StringReadOptions): string {
+        return read_memory_string(this.handle, address, options)
+    }
+
+    writeMemoryString(address: Address, value: string, options?: StringWriteOptions): void {
+        write_memory_string(this.handle, address, value, options)
     }
 
-    writeMemoryString(address: number, value: string): void {
-        write_memory_string(this.handle, address, value)
+    readStdString(
+        address: Address,
+        layout: StringLayout,
+        encoding?: TextEncoding,
+        pointerSize?: PointerSize,
+    ): string {
+        return read_std_string(this.handle, address, layout, encoding, pointerSize)
+    }
+
+    readVector<T extends TypeTag | number>(address: Address, element: T, pointerSize?: PointerSize): VectorElement<T>[] {
+        return read_vector(this.handle, address, element, pointerSize)
+    }
+
+    readPascalString(address: Address, prefixSize: 1 | 2 | 4, encoding?: TextEncoding): string {
+        return read_pascal_string(this.handle, address, prefixSize, encoding)
+    }
+
+    readClrString(object: Address, pointerSize?: PointerSize): string {
+        return read_clr_string(this.handle, object, pointerSize)
+    }
+
+    readClrArray<T extends TypeTag | number>(object: Address, element: T, pointerSize?: PointerSize): VectorElement<T>[] {
+        return read_clr_array(this.handle, object, element, pointerSize)
+    }
+
+    readClrList<T extends TypeTag | number>(object: Address, element: T, pointerSize?: PointerSize): VectorElement<T>[] {
+        return read_clr_list(this.handle, object, element, pointerSize)
+    }
+
+    readClrDictionary<K extends TypeTag | number, V extends TypeTag | number>(
+        object: Address,
+        key: K,
+        value: V,
+        pointerSize?: PointerSize,
+    ): [VectorElement<K>, VectorElement<V>][] {
+        return read_clr_dictionary(this.handle, object, key, value, pointerSize)
     }
 
     getProcessPath(): string {
@@ -145,4 +541,8 @@ export class Process {
     getProcessModules(): Module[] {
         return get_process_modules(this.id)
     }
+
+    mono(offsets?: MonoOffsets): MonoRuntime {
+        return new MonoRuntime(this, mono_open(this.handle, this.id, offsets))
+    }
 }
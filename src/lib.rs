@@ -1,8 +1,449 @@
 mod mem;
 
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::Duration;
+
 use neon::{prelude::*, types::buffer::TypedArray};
 use windows::Win32::Foundation::*;
 
+impl Finalize for mem::CancelToken {}
+
+impl Finalize for mem::Signature {}
+
+impl Finalize for mem::SignatureDatabase {}
+
+impl Finalize for mem::Schema {}
+
+impl Finalize for mem::MonoRuntime {}
+
+struct SharedSignatureCache(Arc<Mutex<mem::SignatureCache>>);
+
+impl Finalize for SharedSignatureCache {}
+
+struct SharedValueScan(Arc<Mutex<mem::ValueScan>>);
+
+impl Finalize for SharedValueScan {}
+
+// `value_scan_next` holds the lock for the whole scan, so the main thread must not wait on it.
+fn lock_value_scan<'a>(
+    cx: &mut FunctionContext,
+    scan: &'a Mutex<mem::ValueScan>,
+) -> NeonResult<MutexGuard<'a, mem::ValueScan>> {
+    match scan.try_lock() {
+        Ok(scan) => Ok(scan),
+        Err(TryLockError::WouldBlock) => cx.throw_error("Scan in progress"),
+        Err(TryLockError::Poisoned(err)) => cx.throw_error(err.to_string()),
+    }
+}
+
+fn signature_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<mem::Signature> {
+    let value = cx.argument::<JsValue>(i)?;
+    if let Ok(signature) = value.downcast::<JsBox<mem::Signature>, _>(cx) {
+        return Ok((**signature).clone());
+    }
+    let pattern = value.downcast_or_throw::<JsString, _>(cx)?.value(cx);
+    match mem::Signature::new(&pattern) {
+        Ok(signature) => Ok(signature),
+        Err(err) => cx.throw_error(format!("Invalid signature: {}", err)),
+    }
+}
+
+// Progress events are throttled so a scan over many small regions does not flood the event loop.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+struct ScanOptions {
+    control: mem::ScanControl,
+    on_progress: Option<Root<JsFunction>>,
+}
+
+fn scan_options(cx: &mut FunctionContext, i: i32) -> NeonResult<ScanOptions> {
+    let options = match cx.argument_opt(i) {
+        Some(options) if options.is_a::<JsObject, _>(cx) => {
+            options.downcast_or_throw::<JsObject, _>(cx)?
+        }
+        _ => {
+            return Ok(ScanOptions {
+                control: mem::ScanControl::default(),
+                on_progress: None,
+            })
+        }
+    };
+    let token = options
+        .get_opt::<JsBox<mem::CancelToken>, _, _>(cx, "token")?
+        .map(|token| (**token).clone());
+    let timeout = options
+        .get_opt::<JsNumber, _, _>(cx, "timeout")?
+        .map(|timeout| Duration::from_millis(timeout.value(cx) as u64));
+    let on_progress = options
+        .get_opt::<JsFunction, _, _>(cx, "onProgress")?
+        .map(|callback| callback.root(cx));
+    Ok(ScanOptions {
+        control: mem::ScanControl::new(token, timeout),
+        on_progress,
+    })
+}
+
+struct ProgressReporter {
+    channel: Channel,
+    callback: Arc<Root<JsFunction>>,
+    throttle: mem::ProgressThrottle,
+}
+
+impl ProgressReporter {
+    fn send(&self, progress: mem::ScanProgress) {
+        let callback = self.callback.clone();
+        self.channel.send(move |mut cx| {
+            let callback = callback.to_inner(&mut cx);
+            let object = cx.empty_object();
+            let bytes_scanned = cx.number(progress.bytes_scanned as f64);
+            let regions_scanned = cx.number(progress.regions_scanned);
+            object.set(&mut cx, "bytesScanned", bytes_scanned)?;
+            object.set(&mut cx, "regionsScanned", regions_scanned)?;
+            callback.call_with(&cx).arg(object).exec(&mut cx)
+        });
+    }
+}
+
+// The reporter is dropped when the scan returns, however it ends, so the last update is
+// always delivered.
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        if let Some(progress) = self.throttle.finish() {
+            self.send(progress);
+        }
+    }
+}
+
+fn progress_reporter(
+    channel: Channel,
+    callback: Option<Root<JsFunction>>,
+) -> impl FnMut(mem::ScanProgress) + Send {
+    let mut reporter = callback.map(|callback| ProgressReporter {
+        channel,
+        callback: Arc::new(callback),
+        throttle: mem::ProgressThrottle::new(PROGRESS_INTERVAL),
+    });
+    move |progress| {
+        if let Some(reporter) = &mut reporter {
+            if let Some(progress) = reporter.throttle.update(progress) {
+                reporter.send(progress);
+            }
+        }
+    }
+}
+
+fn addresses_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<Vec<u64>> {
+    let array = cx.argument::<JsArray>(i)?.to_vec(cx)?;
+    let mut addresses = Vec::with_capacity(array.len());
+    for value in array {
+        let value = value.downcast_or_throw::<JsNumber, _>(cx)?.value(cx);
+        // Offsets may be negative, wrap them like the target's pointer arithmetic would.
+        addresses.push(value as i64 as u64);
+    }
+    Ok(addresses)
+}
+
+// Addresses are numbers, BigInts or expressions such as `"osu!.exe"+0x1A2B3C -> +0x10`.
+fn address_argument(cx: &mut FunctionContext, i: i32, handle: HANDLE) -> NeonResult<u64> {
+    let value = cx.argument::<JsValue>(i)?;
+    // Plain addresses are the per-frame case and never touch the process.
+    if let Some(address) = numeric_address(cx, value)? {
+        return Ok(address);
+    }
+    let mut context = mem::ProcessAddressContext::new(handle);
+    match address_from_js(cx, value, &mut context)? {
+        Ok(address) => Ok(address),
+        Err(err) => cx.throw_error(err.to_string()),
+    }
+}
+
+// A number or BigInt address, `None` for an expression string.
+fn numeric_address(cx: &mut FunctionContext, value: Handle<JsValue>) -> NeonResult<Option<u64>> {
+    if let Ok(address) = value.downcast::<JsNumber, _>(cx) {
+        return Ok(Some(address.value(cx) as u64));
+    }
+    if value.is_a::<JsString, _>(cx) {
+        return Ok(None);
+    }
+    if value.is_a::<JsObject, _>(cx)
+        || value.is_a::<JsUndefined, _>(cx)
+        || value.is_a::<JsNull, _>(cx)
+        || value.is_a::<JsBoolean, _>(cx)
+    {
+        return cx.throw_type_error("Expected an address number, BigInt or expression");
+    }
+    // neon has no BigInt type; its decimal text is exact.
+    let text = value.to_string(cx)?.value(cx);
+    match text.parse() {
+        Ok(address) => Ok(Some(address)),
+        Err(_) => cx.throw_range_error(format!("{} is not a valid address", text)),
+    }
+}
+
+// The outer result fails on a value that is not an address at all, the inner one when an
+// expression cannot be evaluated. Batches share one context so modules are enumerated once.
+fn address_from_js(
+    cx: &mut FunctionContext,
+    value: Handle<JsValue>,
+    context: &mut mem::ProcessAddressContext,
+) -> NeonResult<Result<u64, mem::AddressError>> {
+    if let Some(address) = numeric_address(cx, value)? {
+        return Ok(Ok(address));
+    }
+    let source = value.downcast_or_throw::<JsString, _>(cx)?.value(cx);
+    Ok(mem::AddressExpression::parse(&source).and_then(|expression| expression.evaluate(context)))
+}
+
+fn pointer_width_argument(
+    cx: &mut FunctionContext,
+    i: i32,
+    handle: HANDLE,
+) -> NeonResult<mem::PointerWidth> {
+    match cx.argument_opt(i) {
+        Some(size) if size.is_a::<JsNumber, _>(cx) => {
+            let size = size.downcast_or_throw::<JsNumber, _>(cx)?.value(cx);
+            match mem::PointerWidth::from_size(size as usize) {
+                Some(width) => Ok(width),
+                None => cx.throw_range_error("Pointer size must be 4 or 8"),
+            }
+        }
+        _ => Ok(mem::PointerWidth::of_process(handle)),
+    }
+}
+
+fn value_type_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<mem::ValueType> {
+    let name = cx.argument::<JsString>(i)?.value(cx);
+    match mem::ValueType::from_name(&name) {
+        Some(ty) => Ok(ty),
+        None => cx.throw_type_error(format!("Unknown type {}", name)),
+    }
+}
+
+fn value_from_js(
+    cx: &mut FunctionContext,
+    value: Handle<JsValue>,
+    ty: mem::ValueType,
+) -> NeonResult<mem::Value> {
+    if let Ok(value) = value.downcast::<JsBoolean, _>(cx) {
+        let value = if value.value(cx) { 1.0 } else { 0.0 };
+        return Ok(mem::Value::from_f64(ty, value));
+    }
+    if let Ok(value) = value.downcast::<JsNumber, _>(cx) {
+        let value = value.value(cx);
+        // Numbers can't hold every 64-bit integer, so those must be exact rather than saturate.
+        if !matches!(ty, mem::ValueType::I64 | mem::ValueType::U64) {
+            return Ok(mem::Value::from_f64(ty, value));
+        }
+        return match mem::Value::from_safe_integer(ty, value) {
+            Some(value) => Ok(value),
+            None => cx.throw_range_error(format!(
+                "{} is not a safe integer in the target type's range, use a BigInt",
+                value
+            )),
+        };
+    }
+    if value.is_a::<JsObject, _>(cx)
+        || value.is_a::<JsUndefined, _>(cx)
+        || value.is_a::<JsNull, _>(cx)
+    {
+        return cx.throw_type_error("Expected a number, boolean or BigInt");
+    }
+    // BigInts and decimal strings go through their text so 64-bit values stay exact.
+    let text = value.to_string(cx)?.value(cx);
+    match mem::Value::from_decimal(ty, &text) {
+        Some(value) => Ok(value),
+        None => cx.throw_range_error(format!("{} does not fit the target type", text)),
+    }
+}
+
+// neon has no BigInt type, so 64-bit integers are built by calling the global `BigInt` with
+// their decimal text.
+fn bigint_to_js<'a, C: Context<'a>>(cx: &mut C, text: String) -> JsResult<'a, JsValue> {
+    let bigint = cx.global().get::<JsFunction, _, _>(cx, "BigInt")?;
+    let undefined = cx.undefined();
+    let text = cx.string(text).upcast::<JsValue>();
+    bigint.call(cx, undefined, [text])
+}
+
+// 64-bit integers become BigInts so they stay exact; only the `_lossy` exports return them as
+// numbers.
+fn value_to_js<'a, C: Context<'a>>(cx: &mut C, value: mem::Value) -> JsResult<'a, JsValue> {
+    match value {
+        mem::Value::Bool(value) => Ok(cx.boolean(value).upcast()),
+        mem::Value::I64(value) => bigint_to_js(cx, value.to_string()),
+        mem::Value::U64(value) => bigint_to_js(cx, value.to_string()),
+        value => Ok(cx.number(value.as_f64()).upcast()),
+    }
+}
+
+// A value type, or 'pointer' for the pointer width of the process. Pointers are returned as
+// numbers like every other address.
+fn type_tag_argument(
+    cx: &mut FunctionContext,
+    i: i32,
+    handle: HANDLE,
+) -> NeonResult<(mem::ValueType, bool)> {
+    let name = cx.argument::<JsString>(i)?.value(cx);
+    match name.as_str() {
+        "pointer" => Ok((mem::PointerWidth::of_process(handle).value_type(), true)),
+        name => match mem::ValueType::from_name(name) {
+            Some(ty) => Ok((ty, false)),
+            None => cx.throw_type_error(format!("Unknown type {}", name)),
+        },
+    }
+}
+
+fn endianness_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<mem::Endianness> {
+    let name = match cx.argument_opt(i) {
+        Some(name) if name.is_a::<JsString, _>(cx) => {
+            name.downcast_or_throw::<JsString, _>(cx)?.value(cx)
+        }
+        _ => return Ok(mem::Endianness::Little),
+    };
+    match mem::Endianness::from_name(&name) {
+        Some(endianness) => Ok(endianness),
+        None => cx.throw_type_error(format!("Unknown endianness {}", name)),
+    }
+}
+
+// Filters are objects such as `{ kind: 'exact', value: 100 }` or `{ kind: 'range', min: 0, max: 10 }`.
+// Float scans also read `tolerance` ('exact', 'rounded', 'truncated' or an epsilon) and `finiteOnly`.
+fn scan_filter_argument(
+    cx: &mut FunctionContext,
+    i: i32,
+    ty: mem::ValueType,
+) -> NeonResult<(mem::ScanFilter, mem::FloatOptions)> {
+    let filter = cx.argument::<JsObject>(i)?;
+    scan_filter_from_object(cx, filter, ty)
+}
+
+fn scan_filter_from_object(
+    cx: &mut FunctionContext,
+    filter: Handle<JsObject>,
+    ty: mem::ValueType,
+) -> NeonResult<(mem::ScanFilter, mem::FloatOptions)> {
+    // Without a kind, a filter with a value is an exact match and one without accepts anything.
+    let kind = match filter.get_opt::<JsString, _, _>(cx, "kind")? {
+        Some(kind) => kind.value(cx),
+        None if filter.get_value(cx, "value")?.is_a::<JsUndefined, _>(cx) => "unknown".to_string(),
+        None => "exact".to_string(),
+    };
+    let operand = |cx: &mut FunctionContext, key: &str| -> NeonResult<mem::Value> {
+        let value = filter.get_value(cx, key)?;
+        value_from_js(cx, value, ty)
+    };
+    let scan_filter = match kind.as_str() {
+        "exact" => mem::ScanFilter::Exact(operand(cx, "value")?),
+        "range" => mem::ScanFilter::Range(operand(cx, "min")?, operand(cx, "max")?),
+        "unknown" => mem::ScanFilter::Unknown,
+        "changed" => mem::ScanFilter::Changed,
+        "unchanged" => mem::ScanFilter::Unchanged,
+        "increased" => mem::ScanFilter::Increased,
+        "decreased" => mem::ScanFilter::Decreased,
+        "increasedBy" => mem::ScanFilter::IncreasedBy(operand(cx, "value")?),
+        "decreasedBy" => mem::ScanFilter::DecreasedBy(operand(cx, "value")?),
+        _ => return cx.throw_type_error(format!("Unknown scan filter {}", kind)),
+    };
+
+    let mut float = mem::FloatOptions::default();
+    let tolerance = filter.get_value(cx, "tolerance")?;
+    if let Ok(epsilon) = tolerance.downcast::<JsNumber, _>(cx) {
+        float.tolerance = mem::FloatTolerance::Epsilon(epsilon.value(cx));
+    } else if let Ok(tolerance) = tolerance.downcast::<JsString, _>(cx) {
+        float.tolerance = match tolerance.value(cx).as_str() {
+            "exact" => mem::FloatTolerance::Exact,
+            "rounded" => mem::FloatTolerance::Rounded,
+            "truncated" => mem::FloatTolerance::Truncated,
+            tolerance => return cx.throw_type_error(format!("Unknown tolerance {}", tolerance)),
+        };
+    }
+    if let Some(finite_only) = filter.get_opt::<JsBoolean, _, _>(cx, "finiteOnly")? {
+        float.finite_only = finite_only.value(cx);
+    }
+    Ok((scan_filter, float))
+}
+
+// Fields are `{ name, offset, type, count?, fields? }`. `type` is a value type, 'pointer' or
+// 'struct'; pointers with `fields` are followed, `count` turns the field into a fixed array.
+fn schema_from_array(cx: &mut FunctionContext, array: Handle<JsArray>) -> NeonResult<mem::Schema> {
+    let mut fields = Vec::new();
+    for value in array.to_vec(cx)? {
+        let object = value.downcast_or_throw::<JsObject, _>(cx)?;
+        let name = object.get::<JsString, _, _>(cx, "name")?.value(cx);
+        let offset = object.get::<JsNumber, _, _>(cx, "offset")?.value(cx) as usize;
+        let type_name = object.get::<JsString, _, _>(cx, "type")?.value(cx);
+        let nested = match object.get_opt::<JsArray, _, _>(cx, "fields")? {
+            Some(fields) => Some(schema_from_array(cx, fields)?),
+            None => None,
+        };
+        let mut ty = match (type_name.as_str(), nested) {
+            ("pointer", nested) => mem::FieldType::Pointer(nested),
+            ("struct", Some(nested)) => mem::FieldType::Struct(nested),
+            ("struct", None) => {
+                return cx.throw_type_error(format!("Struct field {} needs fields", name))
+            }
+            (type_name, _) => match mem::ValueType::from_name(type_name) {
+                Some(ty) => mem::FieldType::Value(ty),
+                None => return cx.throw_type_error(format!("Unknown type {}", type_name)),
+            },
+        };
+        if let Some(count) = object.get_opt::<JsNumber, _, _>(cx, "count")? {
+            ty = mem::FieldType::Array(Box::new(ty), count.value(cx) as usize);
+        }
+        fields.push(mem::SchemaField { name, offset, ty });
+    }
+    Ok(mem::Schema { fields })
+}
+
+fn schema_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<mem::Schema> {
+    let value = cx.argument::<JsValue>(i)?;
+    if let Ok(schema) = value.downcast::<JsBox<mem::Schema>, _>(cx) {
+        return Ok((**schema).clone());
+    }
+    let array = value.downcast_or_throw::<JsArray, _>(cx)?;
+    schema_from_array(cx, array)
+}
+
+fn field_value_to_js<'a, C: Context<'a>>(
+    cx: &mut C,
+    value: mem::FieldValue,
+) -> JsResult<'a, JsValue> {
+    Ok(match value {
+        mem::FieldValue::Value(value) => value_to_js(cx, value)?,
+        mem::FieldValue::Pointer(address) => cx.number(address as f64).upcast(),
+        mem::FieldValue::Null => cx.null().upcast(),
+        mem::FieldValue::Struct(fields) => {
+            let object = cx.empty_object();
+            for (name, value) in fields {
+                let value = field_value_to_js(cx, value)?;
+                object.set(cx, name.as_str(), value)?;
+            }
+            object.upcast()
+        }
+        mem::FieldValue::Array(values) => {
+            let array = cx.empty_array();
+            for (i, value) in values.into_iter().enumerate() {
+                let value = field_value_to_js(cx, value)?;
+                array.set(cx, i as u32, value)?;
+            }
+            array.upcast()
+        }
+    })
+}
+
+fn throw_pointer_chain_error<T>(
+    cx: &mut FunctionContext,
+    err: mem::PointerChainError,
+) -> NeonResult<T> {
+    let error = cx.error(err.to_string())?;
+    let hop = cx.number(err.hop as f64);
+    let address = cx.number(err.address as f64);
+    error.set(cx, "hop", hop)?;
+    error.set(cx, "address", address)?;
+    cx.throw(error)
+}
+
 fn open_process(mut cx: FunctionContext) -> JsResult<JsObject> {
     let process_name = cx.argument::<JsString>(0)?.value(&mut cx);
     let process = match mem::open_process(process_name.as_str()) {
@@ -24,6 +465,16 @@ fn open_process(mut cx: FunctionContext) -> JsResult<JsObject> {
     Ok(obj)
 }
 
+fn create_cancel_token(mut cx: FunctionContext) -> JsResult<JsBox<mem::CancelToken>> {
+    Ok(cx.boxed(mem::CancelToken::new()))
+}
+
+fn cancel_scan(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let token = cx.argument::<JsBox<mem::CancelToken>>(0)?;
+    token.cancel();
+    Ok(cx.undefined())
+}
+
 fn close_handle(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
     let result = mem::close_handle(HANDLE(handle as isize));
@@ -32,9 +483,9 @@ fn close_handle(mut cx: FunctionContext) -> JsResult<JsBoolean> {
 
 fn sig_scan_sync(mut cx: FunctionContext) -> JsResult<JsValue> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let signature = cx.argument::<JsString>(1)?.value(&mut cx);
-    let base_address = cx.argument::<JsNumber>(2)?.value(&mut cx) as u32;
-    let result = mem::sig_scan(HANDLE(handle as isize), signature.as_str(), base_address);
+    let signature = signature_argument(&mut cx, 1)?;
+    let base_address = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let result = mem::sig_scan(HANDLE(handle as isize), &signature, base_address);
     match result {
         Some(x) => Ok(cx.number(x as f64).upcast()),
         None => Ok(cx.undefined().upcast()),
@@ -43,14 +494,117 @@ fn sig_scan_sync(mut cx: FunctionContext) -> JsResult<JsValue> {
 
 fn sig_scan(mut cx: FunctionContext) -> JsResult<JsPromise> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let signature = cx.argument::<JsString>(1)?.value(&mut cx);
-    let base_address = cx.argument::<JsNumber>(2)?.value(&mut cx) as u32;
+    let signature = signature_argument(&mut cx, 1)?;
+    let base_address = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let ScanOptions {
+        control,
+        on_progress,
+    } = scan_options(&mut cx, 3)?;
+    let on_progress = progress_reporter(cx.channel(), on_progress);
 
     let promise = cx
-        .task(move || mem::sig_scan(HANDLE(handle as isize), &signature, base_address))
+        .task(move || {
+            mem::sig_scan_with_control(
+                HANDLE(handle as isize),
+                &signature,
+                base_address,
+                &control,
+                on_progress,
+            )
+        })
         .promise::<JsValue, _>(move |mut cx, result| match result {
-            Some(x) => Ok(cx.number(x as f64).upcast()),
-            None => Ok(cx.undefined().upcast()),
+            Ok(Some(x)) => Ok(cx.number(x as f64).upcast()),
+            Ok(None) => Ok(cx.undefined().upcast()),
+            Err(err) => cx.throw_error(err.to_string()),
+        });
+    Ok(promise)
+}
+
+// Fields are `{ type, offset?, kind?, value?, ... }` filters, or `{ skip: n }` to leave a gap.
+// A field without an offset follows the previous one.
+fn group_fields_argument(
+    cx: &mut FunctionContext,
+    i: i32,
+    handle: HANDLE,
+) -> NeonResult<mem::GroupScan> {
+    let array = cx.argument::<JsArray>(i)?.to_vec(cx)?;
+    let mut fields = Vec::with_capacity(array.len());
+    let mut cursor = 0;
+    for value in array {
+        let object = value.downcast_or_throw::<JsObject, _>(cx)?;
+        if let Some(skip) = object.get_opt::<JsNumber, _, _>(cx, "skip")? {
+            cursor += skip.value(cx) as usize;
+            continue;
+        }
+        let name = object.get::<JsString, _, _>(cx, "type")?.value(cx);
+        let ty = match name.as_str() {
+            "pointer" => mem::PointerWidth::of_process(handle).value_type(),
+            name => match mem::ValueType::from_name(name) {
+                Some(ty) => ty,
+                None => return cx.throw_type_error(format!("Unknown type {}", name)),
+            },
+        };
+        if let Some(offset) = object.get_opt::<JsNumber, _, _>(cx, "offset")? {
+            cursor = offset.value(cx) as usize;
+        }
+        let (filter, float) = scan_filter_from_object(cx, object, ty)?;
+        if filter.is_relative() {
+            return cx.throw_error("Group fields need an exact, range or unknown filter");
+        }
+        fields.push(mem::GroupField {
+            offset: cursor,
+            ty,
+            filter,
+            float,
+        });
+        cursor += ty.size();
+    }
+    let group = mem::GroupScan::new(fields);
+    if group.is_empty() {
+        return cx.throw_error("Group scan needs at least one field");
+    }
+    Ok(group)
+}
+
+fn group_scan(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let group = group_fields_argument(&mut cx, 1, handle)?;
+    let base_address = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let mut alignment = 4;
+    if let Some(options) = cx.argument_opt(3) {
+        if let Ok(options) = options.downcast::<JsObject, _>(&mut cx) {
+            if let Some(value) = options.get_opt::<JsNumber, _, _>(&mut cx, "alignment")? {
+                alignment = value.value(&mut cx) as usize;
+            }
+        }
+    }
+    let ScanOptions {
+        control,
+        on_progress,
+    } = scan_options(&mut cx, 3)?;
+    let on_progress = progress_reporter(cx.channel(), on_progress);
+
+    let promise = cx
+        .task(move || {
+            mem::group_scan_with_control(
+                handle,
+                &group,
+                base_address,
+                alignment,
+                &control,
+                on_progress,
+            )
+        })
+        .promise::<JsArray, _>(move |mut cx, result| match result {
+            Ok(addresses) => {
+                let array = cx.empty_array();
+                for (i, address) in addresses.into_iter().enumerate() {
+                    let address = cx.number(address as f64);
+                    array.set(&mut cx, i as u32, address)?;
+                }
+                Ok(array)
+            }
+            Err(err) => cx.throw_error(err.to_string()),
         });
     Ok(promise)
 }
@@ -58,7 +612,7 @@ fn sig_scan(mut cx: FunctionContext) -> JsResult<JsPromise> {
 fn sig_scan_module_sync(mut cx: FunctionContext) -> JsResult<JsValue> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
     let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
-    let signature = cx.argument::<JsString>(2)?.value(&mut cx);
+    let signature = signature_argument(&mut cx, 2)?;
     let module_name = cx.argument::<JsString>(3)?.value(&mut cx);
     let result = mem::sig_scan_module(
         HANDLE(handle as isize),
@@ -75,16 +629,190 @@ fn sig_scan_module_sync(mut cx: FunctionContext) -> JsResult<JsValue> {
 fn sig_scan_module(mut cx: FunctionContext) -> JsResult<JsPromise> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
     let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
-    let signature = cx.argument::<JsString>(2)?.value(&mut cx);
+    let signature = signature_argument(&mut cx, 2)?;
     let module_name = cx.argument::<JsString>(3)?.value(&mut cx);
+    let ScanOptions {
+        control,
+        on_progress,
+    } = scan_options(&mut cx, 4)?;
+    let on_progress = progress_reporter(cx.channel(), on_progress);
 
     let promise = cx
         .task(move || {
-            mem::sig_scan_module(
+            mem::sig_scan_module_with_control(
                 HANDLE(handle as isize),
                 process_id,
                 &signature,
                 &module_name,
+                &control,
+                on_progress,
+            )
+        })
+        .promise::<JsValue, _>(move |mut cx, result| match result {
+            Ok(Some(x)) => Ok(cx.number(x as f64).upcast()),
+            Ok(None) => Ok(cx.undefined().upcast()),
+            Err(err) => cx.throw_error(err.to_string()),
+        });
+    Ok(promise)
+}
+
+fn sig_scan_reverse(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let signature = signature_argument(&mut cx, 2)?;
+    let address = cx.argument::<JsNumber>(3)?.value(&mut cx) as u64;
+    let result = mem::sig_scan_reverse(HANDLE(handle as isize), process_id, &signature, address);
+    match result {
+        Some(x) => Ok(cx.number(x as f64).upcast()),
+        None => Ok(cx.undefined().upcast()),
+    }
+}
+
+fn find_function_start(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let result = mem::find_function_start(HANDLE(handle as isize), process_id, address);
+    match result {
+        Some(x) => Ok(cx.number(x as f64).upcast()),
+        None => Ok(cx.undefined().upcast()),
+    }
+}
+
+fn references_array<'a, C: Context<'a>>(
+    cx: &mut C,
+    references: Vec<mem::Reference>,
+) -> JsResult<'a, JsArray> {
+    let array = cx.empty_array();
+    for (i, reference) in references.iter().enumerate() {
+        let object = cx.empty_object();
+        let address = cx.number(reference.address as f64);
+        let kind = cx.string(reference.kind.as_str());
+        object.set(cx, "address", address)?;
+        object.set(cx, "kind", kind)?;
+        array.set(cx, i as u32, object)?;
+    }
+    Ok(array)
+}
+
+fn find_references(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let target = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let module_name = match cx.argument_opt(3) {
+        Some(name) if name.is_a::<JsString, _>(&mut cx) => Some(
+            name.downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx),
+        ),
+        _ => None,
+    };
+    let ScanOptions {
+        control,
+        on_progress,
+    } = scan_options(&mut cx, 4)?;
+    let on_progress = progress_reporter(cx.channel(), on_progress);
+
+    let promise = cx
+        .task(move || {
+            let handle = HANDLE(handle as isize);
+            match module_name {
+                Some(module_name) => {
+                    mem::find_module_references(handle, process_id, &module_name, target)
+                        .ok_or(format!("Module {} not found", module_name))
+                }
+                None => mem::find_process_references(handle, target, &control, on_progress)
+                    .map_err(|err| err.to_string()),
+            }
+        })
+        .promise::<JsArray, _>(move |mut cx, result| match result {
+            Ok(references) => references_array(&mut cx, references),
+            Err(err) => cx.throw_error(err),
+        });
+    Ok(promise)
+}
+
+fn find_string_references(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let text = cx.argument::<JsString>(2)?.value(&mut cx);
+    if text.is_empty() {
+        return cx.throw_range_error("Text must not be empty");
+    }
+    let module_name = match cx.argument_opt(3) {
+        Some(name) if name.is_a::<JsString, _>(&mut cx) => Some(
+            name.downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx),
+        ),
+        _ => None,
+    };
+
+    let promise = cx
+        .task(move || {
+            mem::find_string_references(
+                HANDLE(handle as isize),
+                process_id,
+                module_name.as_deref(),
+                &text,
+            )
+            .ok_or_else(|| format!("Module {} not found", module_name.unwrap_or_default()))
+        })
+        .promise::<JsArray, _>(move |mut cx, result| {
+            let references = match result {
+                Ok(references) => references,
+                Err(err) => return cx.throw_error(err),
+            };
+            let array = cx.empty_array();
+            for (i, reference) in references.iter().enumerate() {
+                let object = cx.empty_object();
+                let address = cx.number(reference.reference.address as f64);
+                let kind = cx.string(reference.reference.kind.as_str());
+                let string_address = cx.number(reference.string_address as f64);
+                let encoding = cx.string(if reference.wide { "utf16" } else { "ascii" });
+                object.set(&mut cx, "address", address)?;
+                object.set(&mut cx, "kind", kind)?;
+                object.set(&mut cx, "stringAddress", string_address)?;
+                object.set(&mut cx, "encoding", encoding)?;
+                array.set(&mut cx, i as u32, object)?;
+            }
+            Ok(array)
+        });
+    Ok(promise)
+}
+
+fn sig_scan_module_cached_sync(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let signature = signature_argument(&mut cx, 2)?;
+    let module_name = cx.argument::<JsString>(3)?.value(&mut cx);
+    let cache = cx.argument::<JsBox<SharedSignatureCache>>(4)?.0.clone();
+    let result = mem::sig_scan_module_cached(
+        HANDLE(handle as isize),
+        process_id,
+        &signature,
+        &module_name,
+        &cache,
+    );
+    match result {
+        Some(x) => Ok(cx.number(x as f64).upcast()),
+        None => Ok(cx.undefined().upcast()),
+    }
+}
+
+fn sig_scan_module_cached(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let signature = signature_argument(&mut cx, 2)?;
+    let module_name = cx.argument::<JsString>(3)?.value(&mut cx);
+    let cache = cx.argument::<JsBox<SharedSignatureCache>>(4)?.0.clone();
+
+    let promise = cx
+        .task(move || {
+            mem::sig_scan_module_cached(
+                HANDLE(handle as isize),
+                process_id,
+                &signature,
+                &module_name,
+                &cache,
             )
         })
         .promise::<JsValue, _>(move |mut cx, result| match result {
@@ -94,23 +822,614 @@ fn sig_scan_module(mut cx: FunctionContext) -> JsResult<JsPromise> {
     Ok(promise)
 }
 
+fn signature_cache_load(mut cx: FunctionContext) -> JsResult<JsBox<SharedSignatureCache>> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    match mem::SignatureCache::load(Path::new(&path)) {
+        Ok(cache) => Ok(cx.boxed(SharedSignatureCache(Arc::new(Mutex::new(cache))))),
+        Err(err) => cx.throw_error(err.to_string()),
+    }
+}
+
+fn signature_cache_save(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let cache = cx.argument::<JsBox<SharedSignatureCache>>(0)?.0.clone();
+    let path = cx.argument::<JsString>(1)?.value(&mut cx);
+    let result = cache.lock().unwrap().save(Path::new(&path));
+    match result {
+        Ok(()) => Ok(cx.undefined()),
+        Err(err) => cx.throw_error(err.to_string()),
+    }
+}
+
+fn signature_database_load(mut cx: FunctionContext) -> JsResult<JsBox<mem::SignatureDatabase>> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    match mem::SignatureDatabase::load(Path::new(&path)) {
+        Ok(database) => Ok(cx.boxed(database)),
+        Err(err) => cx.throw_error(format!("Failed to load {}: {}", path, err)),
+    }
+}
+
+fn resolve_signatures(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let database = (**cx.argument::<JsBox<mem::SignatureDatabase>>(2)?).clone();
+    let version = match cx.argument_opt(3) {
+        Some(version) if version.is_a::<JsString, _>(&mut cx) => Some(
+            version
+                .downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx),
+        ),
+        _ => None,
+    };
+    let cache = match cx.argument_opt(4) {
+        Some(cache) if cache.is_a::<JsBox<SharedSignatureCache>, _>(&mut cx) => Some(
+            cache
+                .downcast_or_throw::<JsBox<SharedSignatureCache>, _>(&mut cx)?
+                .0
+                .clone(),
+        ),
+        _ => None,
+    };
+
+    let promise = cx
+        .task(move || {
+            mem::resolve_signatures(
+                HANDLE(handle as isize),
+                process_id,
+                &database,
+                version.as_deref(),
+                cache.as_deref(),
+            )
+        })
+        .promise::<JsObject, _>(move |mut cx, resolutions| {
+            let obj = cx.empty_object();
+            let resolved = cx.empty_object();
+            let versions = cx.empty_object();
+            let failed = cx.empty_array();
+            let mut i = 0;
+            for resolution in resolutions {
+                match resolution.result {
+                    Ok(address) => {
+                        let address = cx.number(address as f64);
+                        resolved.set(&mut cx, resolution.name.as_str(), address)?;
+                        if let Some(version) = &resolution.version {
+                            let version = cx.string(version);
+                            versions.set(&mut cx, resolution.name.as_str(), version)?;
+                        }
+                    }
+                    Err(err) => {
+                        let failure = cx.empty_object();
+                        let name = cx.string(&resolution.name);
+                        let error = cx.string(&err);
+                        failure.set(&mut cx, "name", name)?;
+                        failure.set(&mut cx, "error", error)?;
+                        failed.set(&mut cx, i, failure)?;
+                        i += 1;
+                    }
+                }
+            }
+            obj.set(&mut cx, "resolved", resolved)?;
+            obj.set(&mut cx, "versions", versions)?;
+            obj.set(&mut cx, "failed", failed)?;
+            Ok(obj)
+        });
+    Ok(promise)
+}
+
+fn compile_signature(mut cx: FunctionContext) -> JsResult<JsBox<mem::Signature>> {
+    let signature = signature_argument(&mut cx, 0)?;
+    Ok(cx.boxed(signature))
+}
+
+fn signature_info(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let signature = cx.argument::<JsBox<mem::Signature>>(0)?;
+    let obj = cx.empty_object();
+    let length = cx.number(signature.len() as f64);
+    let wildcards = cx.number(signature.wildcard_count() as f64);
+
+    obj.set(&mut cx, "length", length)?;
+    obj.set(&mut cx, "wildcards", wildcards)?;
+
+    Ok(obj)
+}
+
+fn signature_scan_buffer(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let signature = cx.argument::<JsBox<mem::Signature>>(0)?;
+    let buffer = cx.argument::<JsBuffer>(1)?;
+    let result = signature.scan(buffer.as_slice(&cx));
+    match result {
+        Some(x) => Ok(cx.number(x as f64).upcast()),
+        None => Ok(cx.undefined().upcast()),
+    }
+}
+
+fn evaluate_address(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let address = address_argument(&mut cx, 1, handle)?;
+    Ok(cx.number(address as f64))
+}
+
+fn compile_schema(mut cx: FunctionContext) -> JsResult<JsBox<mem::Schema>> {
+    let schema = schema_argument(&mut cx, 0)?;
+    Ok(cx.boxed(schema))
+}
+
+fn read_struct(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let address = address_argument(&mut cx, 1, handle)?;
+    let schema = schema_argument(&mut cx, 2)?;
+    let depth = match cx.argument_opt(3) {
+        Some(depth) if depth.is_a::<JsNumber, _>(&mut cx) => depth
+            .downcast_or_throw::<JsNumber, _>(&mut cx)?
+            .value(&mut cx) as u32,
+        _ => 1,
+    };
+    let width = pointer_width_argument(&mut cx, 4, handle)?;
+    match mem::read_struct(handle, address, &schema, depth, width) {
+        Some(value) => field_value_to_js(&mut cx, value),
+        None => cx.throw_error(format!("Failed to read memory at 0x{:X}", address)),
+    }
+}
+
+// Entries are `[address, type]` for typed values or `[address, length]` for buffers.
+fn read_memory_batch(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let entries = cx.argument::<JsArray>(1)?.to_vec(&mut cx)?;
+    // The value type of each entry, `None` for raw buffers, and its index in `reads` if its
+    // address resolved.
+    let mut slots = Vec::with_capacity(entries.len());
+    let mut reads = Vec::with_capacity(entries.len());
+    let mut context = mem::ProcessAddressContext::new(handle);
+    for entry in entries {
+        let entry = entry.downcast_or_throw::<JsArray, _>(&mut cx)?;
+        let address = entry.get_value(&mut cx, 0)?;
+        let address = address_from_js(&mut cx, address, &mut context)?;
+        let kind = entry.get_value(&mut cx, 1)?;
+        let (ty, size) = if let Ok(length) = kind.downcast::<JsNumber, _>(&mut cx) {
+            (None, length.value(&mut cx) as usize)
+        } else {
+            let ty = kind
+                .downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx);
+            match mem::ValueType::from_name(&ty) {
+                Some(ty) => (Some(ty), ty.size()),
+                None => return cx.throw_type_error(format!("Unknown type {}", ty)),
+            }
+        };
+        match address {
+            Ok(address) => {
+                slots.push((ty, Some(reads.len())));
+                reads.push((address, size));
+            }
+            Err(_) => slots.push((ty, None)),
+        }
+    }
+    let mut results = mem::read_memory_batch(handle, &reads);
+
+    let values = cx.empty_array();
+    let failed = cx.empty_array();
+    let mut failures = 0;
+    for (i, (ty, read)) in slots.into_iter().enumerate() {
+        let bytes = read.and_then(|read| results[read].take());
+        let value: Handle<JsValue> = match (bytes, ty) {
+            (Some(bytes), Some(ty)) => value_to_js(&mut cx, mem::Value::decode(ty, &bytes))?,
+            (Some(bytes), None) => JsBuffer::external(&mut cx, bytes).upcast(),
+            (None, _) => {
+                let index = cx.number(i as f64);
+                failed.set(&mut cx, failures, index)?;
+                failures += 1;
+                cx.null().upcast()
+            }
+        };
+        values.set(&mut cx, i as u32, value)?;
+    }
+
+    let object = cx.empty_object();
+    object.set(&mut cx, "values", values)?;
+    object.set(&mut cx, "failed", failed)?;
+    Ok(object)
+}
+
 fn read_memory_buffer(mut cx: FunctionContext) -> JsResult<JsBuffer> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let size = cx.argument::<JsNumber>(2)?.value(&mut cx) as usize;
     let buffer: Vec<u8> = mem::read_memory_buffer(HANDLE(handle as isize), address, size);
     let a = JsBuffer::external(&mut cx, buffer);
     Ok(a)
 }
 
+fn read_value(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let address = address_argument(&mut cx, 1, handle)?;
+    let (ty, pointer) = type_tag_argument(&mut cx, 2, handle)?;
+    let endianness = endianness_argument(&mut cx, 3)?;
+    match mem::read_value(handle, address, ty, endianness) {
+        Some(value) if pointer => Ok(cx.number(value.as_f64()).upcast()),
+        Some(value) => value_to_js(&mut cx, value),
+        None => cx.throw_error(format!("Failed to read memory at 0x{:X}", address)),
+    }
+}
+
+// Fills `length` bytes of `array` from `offset`, both counted in bytes whatever the element type.
+fn read_into_array<'a, A>(
+    cx: &mut FunctionContext<'a>,
+    mut array: Handle<'a, A>,
+    handle: HANDLE,
+    address: u64,
+    offset: usize,
+    length: Option<usize>,
+) -> NeonResult<usize>
+where
+    A: TypedArray + Value,
+{
+    let slice = array.as_mut_slice(cx);
+    let size = std::mem::size_of_val(slice);
+    let bytes = unsafe { std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut u8, size) };
+    let length = length.unwrap_or_else(|| size.saturating_sub(offset));
+    match offset.checked_add(length) {
+        Some(end) if end <= size => Ok(mem::read_memory_into(
+            handle,
+            address,
+            &mut bytes[offset..end],
+        )),
+        _ => cx.throw_range_error(format!(
+            "Range {}+{} is outside the {} byte buffer",
+            offset, length, size
+        )),
+    }
+}
+
+fn read_into(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let address = address_argument(&mut cx, 1, handle)?;
+    let target = cx.argument::<JsValue>(2)?;
+    let offset = match cx.argument_opt(3) {
+        Some(value) if !value.is_a::<JsUndefined, _>(&mut cx) => value
+            .downcast_or_throw::<JsNumber, _>(&mut cx)?
+            .value(&mut cx)
+            as usize,
+        _ => 0,
+    };
+    let length = match cx.argument_opt(4) {
+        Some(value) if !value.is_a::<JsUndefined, _>(&mut cx) => Some(
+            value
+                .downcast_or_throw::<JsNumber, _>(&mut cx)?
+                .value(&mut cx) as usize,
+        ),
+        _ => None,
+    };
+
+    // Buffer and Uint8Array come first since they are what callers pass almost always.
+    let read = if let Ok(array) = target.downcast::<JsTypedArray<u8>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsArrayBuffer, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<i8>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<u16>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<i16>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<u32>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<i32>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<f32>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<f64>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<u64>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else if let Ok(array) = target.downcast::<JsTypedArray<i64>, _>(&mut cx) {
+        read_into_array(&mut cx, array, handle, address, offset, length)?
+    } else {
+        return cx.throw_type_error("Expected a Buffer, TypedArray or ArrayBuffer");
+    };
+    Ok(cx.number(read as f64))
+}
+
+fn resolve_pointer_chain(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let addresses = addresses_argument(&mut cx, 1)?;
+    let width = pointer_width_argument(&mut cx, 2, handle)?;
+    match mem::resolve_pointer_chain(handle, &addresses, width) {
+        Ok(address) => Ok(cx.number(address as f64)),
+        Err(err) => throw_pointer_chain_error(&mut cx, err),
+    }
+}
+
+fn read_memory_from_pointer(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let addresses = addresses_argument(&mut cx, 1)?;
+    let ty = value_type_argument(&mut cx, 2)?;
+    let width = pointer_width_argument(&mut cx, 3, handle)?;
+    let (value, address) = match mem::read_memory_from_pointer(handle, &addresses, ty, width) {
+        Ok(result) => result,
+        Err(err) => return throw_pointer_chain_error(&mut cx, err),
+    };
+
+    let obj = cx.empty_object();
+    let value = value_to_js(&mut cx, value)?;
+    let address = cx.number(address as f64);
+
+    obj.set(&mut cx, "value", value)?;
+    obj.set(&mut cx, "address", address)?;
+
+    Ok(obj)
+}
+
+fn read_memory_buffer_from_pointer(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let addresses = addresses_argument(&mut cx, 1)?;
+    let size = cx.argument::<JsNumber>(2)?.value(&mut cx) as usize;
+    let width = pointer_width_argument(&mut cx, 3, handle)?;
+    let (buffer, address) =
+        match mem::read_memory_buffer_from_pointer(handle, &addresses, size, width) {
+            Ok(result) => result,
+            Err(err) => return throw_pointer_chain_error(&mut cx, err),
+        };
+
+    let obj = cx.empty_object();
+    let value = JsBuffer::external(&mut cx, buffer);
+    let address = cx.number(address as f64);
+
+    obj.set(&mut cx, "value", value)?;
+    obj.set(&mut cx, "address", address)?;
+
+    Ok(obj)
+}
+
+fn value_scan_first(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let ty = value_type_argument(&mut cx, 1)?;
+    let (filter, float) = scan_filter_argument(&mut cx, 2, ty)?;
+    if filter.is_relative() {
+        return cx.throw_error("The first scan needs an exact, range or unknown filter");
+    }
+    let ScanOptions {
+        control,
+        on_progress,
+    } = scan_options(&mut cx, 3)?;
+    let on_progress = progress_reporter(cx.channel(), on_progress);
+
+    let promise = cx
+        .task(move || mem::first_value_scan(handle, ty, &filter, &float, &control, on_progress))
+        .promise(move |mut cx, result| match result {
+            Ok(scan) => Ok(cx.boxed(SharedValueScan(Arc::new(Mutex::new(scan))))),
+            Err(err) => cx.throw_error(err.to_string()),
+        });
+    Ok(promise)
+}
+
+fn value_scan_next(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let scan = cx.argument::<JsBox<SharedValueScan>>(1)?.0.clone();
+    let ty = lock_value_scan(&mut cx, &scan)?.value_type();
+    let (filter, float) = scan_filter_argument(&mut cx, 2, ty)?;
+    let ScanOptions {
+        control,
+        on_progress,
+    } = scan_options(&mut cx, 3)?;
+    let on_progress = progress_reporter(cx.channel(), on_progress);
+
+    let promise = cx
+        .task(move || {
+            let mut scan = scan.lock().unwrap();
+            mem::next_value_scan(handle, &mut scan, &filter, &float, &control, on_progress)
+                .map(|()| scan.count())
+        })
+        .promise(move |mut cx, result| match result {
+            Ok(count) => Ok(cx.number(count as f64)),
+            Err(err) => cx.throw_error(err.to_string()),
+        });
+    Ok(promise)
+}
+
+fn value_scan_count(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let scan = cx.argument::<JsBox<SharedValueScan>>(0)?.0.clone();
+    let count = lock_value_scan(&mut cx, &scan)?.count();
+    Ok(cx.number(count as f64))
+}
+
+fn value_scan_results(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let scan = cx.argument::<JsBox<SharedValueScan>>(0)?.0.clone();
+    let offset = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let limit = cx.argument::<JsNumber>(2)?.value(&mut cx) as usize;
+    let results = lock_value_scan(&mut cx, &scan)?.results(offset, limit);
+
+    let array = cx.empty_array();
+    for (i, (address, value)) in results.into_iter().enumerate() {
+        let object = cx.empty_object();
+        let address = cx.number(address as f64);
+        let value = value_to_js(&mut cx, value)?;
+        object.set(&mut cx, "address", address)?;
+        object.set(&mut cx, "value", value)?;
+        array.set(&mut cx, i as u32, object)?;
+    }
+    Ok(array)
+}
+
+fn pointer_paths_array<'a, C: Context<'a>>(
+    cx: &mut C,
+    paths: Vec<mem::PointerPath>,
+) -> JsResult<'a, JsArray> {
+    let array = cx.empty_array();
+    for (i, path) in paths.iter().enumerate() {
+        let object = cx.empty_object();
+        let module = cx.string(&path.module);
+        let base_offset = cx.number(path.base_offset as f64);
+        let offsets = cx.empty_array();
+        for (j, offset) in path.offsets.iter().enumerate() {
+            let offset = cx.number(*offset as f64);
+            offsets.set(cx, j as u32, offset)?;
+        }
+        let expression = cx.string(path.to_string());
+        object.set(cx, "module", module)?;
+        object.set(cx, "baseOffset", base_offset)?;
+        object.set(cx, "offsets", offsets)?;
+        object.set(cx, "expression", expression)?;
+        array.set(cx, i as u32, object)?;
+    }
+    Ok(array)
+}
+
+fn pointer_paths_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<Vec<mem::PointerPath>> {
+    let array = cx.argument::<JsArray>(i)?.to_vec(cx)?;
+    let mut paths = Vec::with_capacity(array.len());
+    for value in array {
+        let object = value.downcast_or_throw::<JsObject, _>(cx)?;
+        let module = object.get::<JsString, _, _>(cx, "module")?.value(cx);
+        let base_offset = object.get::<JsNumber, _, _>(cx, "baseOffset")?.value(cx) as u64;
+        let offsets = object.get::<JsArray, _, _>(cx, "offsets")?.to_vec(cx)?;
+        let mut path = mem::PointerPath {
+            module,
+            base_offset,
+            offsets: Vec::with_capacity(offsets.len()),
+        };
+        for offset in offsets {
+            let offset = offset.downcast_or_throw::<JsNumber, _>(cx)?.value(cx);
+            path.offsets.push(offset as u64);
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn pointer_scan(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let target = address_argument(&mut cx, 2, handle)?;
+    let mut options = mem::PointerScanOptions::default();
+    if let Some(object) = cx.argument_opt(3) {
+        if let Ok(object) = object.downcast::<JsObject, _>(&mut cx) {
+            if let Some(depth) = object.get_opt::<JsNumber, _, _>(&mut cx, "maxDepth")? {
+                options.max_depth = depth.value(&mut cx) as usize;
+            }
+            if let Some(offset) = object.get_opt::<JsNumber, _, _>(&mut cx, "maxOffset")? {
+                options.max_offset = offset.value(&mut cx) as u64;
+            }
+            if let Some(results) = object.get_opt::<JsNumber, _, _>(&mut cx, "maxResults")? {
+                options.max_results = results.value(&mut cx) as usize;
+            }
+            if let Some(nodes) = object.get_opt::<JsNumber, _, _>(&mut cx, "maxNodes")? {
+                options.max_nodes = nodes.value(&mut cx) as usize;
+            }
+        }
+    }
+    let ScanOptions {
+        control,
+        on_progress,
+    } = scan_options(&mut cx, 3)?;
+    let on_progress = progress_reporter(cx.channel(), on_progress);
+
+    let promise = cx
+        .task(move || {
+            mem::pointer_scan(handle, process_id, target, &options, &control, on_progress)
+        })
+        .promise::<JsArray, _>(move |mut cx, result| match result {
+            Ok(paths) => pointer_paths_array(&mut cx, paths),
+            Err(err) => cx.throw_error(err.to_string()),
+        });
+    Ok(promise)
+}
+
+fn pointer_rescan(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let paths = pointer_paths_argument(&mut cx, 2)?;
+    let target = address_argument(&mut cx, 3, handle)?;
+
+    let promise = cx
+        .task(move || mem::rescan_pointer_paths(handle, process_id, &paths, target))
+        .promise::<JsArray, _>(move |mut cx, paths| pointer_paths_array(&mut cx, paths));
+    Ok(promise)
+}
+
+fn pointer_paths_save(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let paths = pointer_paths_argument(&mut cx, 1)?;
+    match mem::save_pointer_paths(Path::new(&path), &paths) {
+        Ok(()) => Ok(cx.undefined()),
+        Err(err) => cx.throw_error(err.to_string()),
+    }
+}
+
+fn pointer_paths_load(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    match mem::load_pointer_paths(Path::new(&path)) {
+        Ok(paths) => pointer_paths_array(&mut cx, paths),
+        Err(err) => cx.throw_error(format!("Failed to load {}: {}", path, err)),
+    }
+}
+
+// Entries are `[address, type, value]` or `[address, buffer]`.
+fn write_memory_batch(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let entries = cx.argument::<JsArray>(1)?.to_vec(&mut cx)?;
+    let mut writes = Vec::with_capacity(entries.len());
+    let mut context = mem::ProcessAddressContext::new(handle);
+    for entry in entries {
+        let entry = entry.downcast_or_throw::<JsArray, _>(&mut cx)?;
+        let address = entry.get_value(&mut cx, 0)?;
+        let address = match address_from_js(&mut cx, address, &mut context)? {
+            Ok(address) => address,
+            Err(err) => return cx.throw_error(err.to_string()),
+        };
+        let kind = entry.get_value(&mut cx, 1)?;
+        let bytes = if let Ok(buffer) = kind.downcast::<JsBuffer, _>(&mut cx) {
+            buffer.as_slice(&cx).to_vec()
+        } else {
+            let ty = kind
+                .downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx);
+            let ty = match mem::ValueType::from_name(&ty) {
+                Some(ty) => ty,
+                None => return cx.throw_type_error(format!("Unknown type {}", ty)),
+            };
+            let value = entry.get_value(&mut cx, 2)?;
+            value_from_js(&mut cx, value, ty)?.to_le_bytes()
+        };
+        writes.push((address, bytes));
+    }
+
+    match mem::write_memory_batch(handle, &writes) {
+        Ok(()) => Ok(cx.undefined()),
+        Err(err) => {
+            let error = cx.error(err.to_string())?;
+            let index = cx.number(err.index as f64);
+            let address = cx.number(err.address as f64);
+            error.set(&mut cx, "index", index)?;
+            error.set(&mut cx, "address", address)?;
+            if let mem::BatchWriteFailure::WriteFailed { restored } = err.failure {
+                let restored = cx.boolean(restored);
+                error.set(&mut cx, "restored", restored)?;
+            }
+            cx.throw(error)
+        }
+    }
+}
+
 fn write_memory_buffer(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let buffer = cx.argument::<JsBuffer>(2)?.as_slice(&cx).to_vec();
     mem::write_memory_buffer(HANDLE(handle as isize), address, &buffer);
     Ok(cx.undefined())
 }
 
+fn write_value(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let address = address_argument(&mut cx, 1, handle)?;
+    let (ty, _) = type_tag_argument(&mut cx, 2, handle)?;
+    let value = cx.argument::<JsValue>(3)?;
+    let value = value_from_js(&mut cx, value, ty)?;
+    let endianness = endianness_argument(&mut cx, 4)?;
+    if !mem::write_value(handle, address, value, endianness) {
+        return cx.throw_error(format!("Failed to write memory at 0x{:X}", address));
+    }
+    Ok(cx.undefined())
+}
+
 fn alloc_memory(mut cx: FunctionContext) -> JsResult<JsNumber> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
     let size = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
@@ -120,74 +1439,94 @@ fn alloc_memory(mut cx: FunctionContext) -> JsResult<JsNumber> {
 
 fn read_memory_u32(mut cx: FunctionContext) -> JsResult<JsNumber> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = mem::read_memory::<u32>(HANDLE(handle as isize), address);
     Ok(cx.number(value as f64))
 }
 
 fn write_memory_u32(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = cx.argument::<JsNumber>(2)?.value(&mut cx) as u32;
     mem::write_memory(HANDLE(handle as isize), address, value);
     Ok(cx.undefined())
 }
 
-fn read_memory_u64(mut cx: FunctionContext) -> JsResult<JsNumber> {
+fn read_memory_u64(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
+    let value = mem::read_memory::<u64>(HANDLE(handle as isize), address);
+    bigint_to_js(&mut cx, value.to_string())
+}
+
+// Values above 2^53 lose precision; `read_memory_u64` returns them exactly as a BigInt.
+fn read_memory_u64_lossy(mut cx: FunctionContext) -> JsResult<JsNumber> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = mem::read_memory::<u64>(HANDLE(handle as isize), address);
     Ok(cx.number(value as f64))
 }
 
+// Accepts a BigInt, or a number for values that fit in one exactly.
 fn write_memory_u64(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
-    let value = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
-    mem::write_memory(HANDLE(handle as isize), address, value);
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
+    let value = cx.argument::<JsValue>(2)?;
+    let value = value_from_js(&mut cx, value, mem::ValueType::U64)?;
+    mem::write_memory_buffer(HANDLE(handle as isize), address, &value.to_le_bytes());
     Ok(cx.undefined())
 }
 
 fn read_memory_i32(mut cx: FunctionContext) -> JsResult<JsNumber> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = mem::read_memory::<i32>(HANDLE(handle as isize), address);
     Ok(cx.number(value as f64))
 }
 
 fn write_memory_i32(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = cx.argument::<JsNumber>(2)?.value(&mut cx) as i32;
     mem::write_memory(HANDLE(handle as isize), address, value);
     Ok(cx.undefined())
 }
 
-fn read_memory_i64(mut cx: FunctionContext) -> JsResult<JsNumber> {
+fn read_memory_i64(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
+    let value = mem::read_memory::<i64>(HANDLE(handle as isize), address);
+    bigint_to_js(&mut cx, value.to_string())
+}
+
+// Values above 2^53 lose precision; `read_memory_i64` returns them exactly as a BigInt.
+fn read_memory_i64_lossy(mut cx: FunctionContext) -> JsResult<JsNumber> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = mem::read_memory::<i64>(HANDLE(handle as isize), address);
     Ok(cx.number(value as f64))
 }
 
+// Accepts a BigInt, or a number for values that fit in one exactly.
 fn write_memory_i64(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
-    let value = cx.argument::<JsNumber>(2)?.value(&mut cx) as i64;
-    mem::write_memory(HANDLE(handle as isize), address, value);
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
+    let value = cx.argument::<JsValue>(2)?;
+    let value = value_from_js(&mut cx, value, mem::ValueType::I64)?;
+    mem::write_memory_buffer(HANDLE(handle as isize), address, &value.to_le_bytes());
     Ok(cx.undefined())
 }
 
 fn read_memory_f32(mut cx: FunctionContext) -> JsResult<JsNumber> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = mem::read_memory::<f32>(HANDLE(handle as isize), address);
     Ok(cx.number(value as f64))
 }
 
 fn write_memory_f32(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = cx.argument::<JsNumber>(2)?.value(&mut cx) as f32;
     mem::write_memory(HANDLE(handle as isize), address, value);
     Ok(cx.undefined())
@@ -195,14 +1534,14 @@ fn write_memory_f32(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
 fn read_memory_f64(mut cx: FunctionContext) -> JsResult<JsNumber> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = mem::read_memory::<f64>(HANDLE(handle as isize), address);
     Ok(cx.number(value as f64))
 }
 
 fn write_memory_f64(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = cx.argument::<JsNumber>(2)?.value(&mut cx) as f64;
     mem::write_memory(HANDLE(handle as isize), address, value);
     Ok(cx.undefined())
@@ -210,41 +1549,256 @@ fn write_memory_f64(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
 fn read_memory_bool(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = mem::read_memory::<bool>(HANDLE(handle as isize), address);
     Ok(cx.boolean(value))
 }
 
 fn write_memory_bool(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let value = cx.argument::<JsBoolean>(2)?.value(&mut cx);
     mem::write_memory(HANDLE(handle as isize), address, value);
     Ok(cx.undefined())
 }
 
+// Options are `{ encoding: 'utf8' | 'latin1' | 'utf16le', maxLength, strict }`.
+fn text_options_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<mem::TextOptions> {
+    let mut options = mem::TextOptions::default();
+    let object = match cx.argument_opt(i) {
+        Some(object) => match object.downcast::<JsObject, _>(cx) {
+            Ok(object) => object,
+            Err(_) => return Ok(options),
+        },
+        None => return Ok(options),
+    };
+    if let Some(encoding) = object.get_opt::<JsString, _, _>(cx, "encoding")? {
+        let name = encoding.value(cx);
+        options.encoding = match mem::TextEncoding::from_name(&name) {
+            Some(encoding) => encoding,
+            None => return cx.throw_type_error(format!("Unknown encoding {}", name)),
+        };
+    }
+    if let Some(length) = object.get_opt::<JsNumber, _, _>(cx, "maxLength")? {
+        options.max_length = length.value(cx) as usize;
+    }
+    if let Some(strict) = object.get_opt::<JsBoolean, _, _>(cx, "strict")? {
+        options.strict = strict.value(cx);
+    }
+    Ok(options)
+}
+
 fn read_memory_string(mut cx: FunctionContext) -> JsResult<JsString> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let mut address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
-    let mut value = String::new();
-    loop {
-        let c = mem::read_memory::<u8>(HANDLE(handle as isize), address);
-        if c == 0x0 {
-            break;
-        }
-        value.push(c as char);
-        address += 1;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
+    let options = text_options_argument(&mut cx, 2)?;
+    match mem::read_string(HANDLE(handle as isize), address, &options) {
+        Ok(value) => Ok(cx.string(&value)),
+        Err(err) => cx.throw_error(format!("{} at 0x{:X}", err, address)),
     }
-    Ok(cx.string(&value))
 }
 
+// Options are `{ encoding: 'utf8' | 'latin1' | 'utf16le', terminate, capacity }`; a NUL
+// terminator is appended unless `terminate` is false.
 fn write_memory_string(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let handle = cx.argument::<JsNumber>(0)?.value(&mut cx);
-    let address = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let address = address_argument(&mut cx, 1, HANDLE(handle as isize))?;
     let string = cx.argument::<JsString>(2)?.value(&mut cx);
-    let buffer = string.into_bytes();
-    mem::write_memory_buffer(HANDLE(handle as isize), address, &buffer);
-    Ok(cx.undefined())
+    let mut options = mem::TextWriteOptions::default();
+    if let Some(object) = cx.argument_opt(3) {
+        if let Ok(object) = object.downcast::<JsObject, _>(&mut cx) {
+            if let Some(encoding) = object.get_opt::<JsString, _, _>(&mut cx, "encoding")? {
+                let name = encoding.value(&mut cx);
+                options.encoding = match mem::TextEncoding::from_name(&name) {
+                    Some(encoding) => encoding,
+                    None => return cx.throw_type_error(format!("Unknown encoding {}", name)),
+                };
+            }
+            if let Some(terminate) = object.get_opt::<JsBoolean, _, _>(&mut cx, "terminate")? {
+                options.terminate = terminate.value(&mut cx);
+            }
+            if let Some(capacity) = object.get_opt::<JsNumber, _, _>(&mut cx, "capacity")? {
+                options.capacity = Some(capacity.value(&mut cx) as usize);
+            }
+        }
+    }
+    match mem::write_string(HANDLE(handle as isize), address, &string, &options) {
+        Ok(()) => Ok(cx.undefined()),
+        Err(err) => cx.throw_error(format!("{} at 0x{:X}", err, address)),
+    }
+}
+
+fn text_encoding_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<mem::TextEncoding> {
+    let name = match cx.argument_opt(i) {
+        Some(name) if name.is_a::<JsString, _>(cx) => {
+            name.downcast_or_throw::<JsString, _>(cx)?.value(cx)
+        }
+        _ => return Ok(mem::TextEncoding::Utf8),
+    };
+    match mem::TextEncoding::from_name(&name) {
+        Some(encoding) => Ok(encoding),
+        None => cx.throw_type_error(format!("Unknown encoding {}", name)),
+    }
+}
+
+// `encoding` is 'utf8' for std::string and 'utf16le' for std::wstring.
+fn read_std_string(mut cx: FunctionContext) -> JsResult<JsString> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let address = address_argument(&mut cx, 1, handle)?;
+    let name = cx.argument::<JsString>(2)?.value(&mut cx);
+    let layout = match mem::StringLayout::from_name(&name) {
+        Some(layout) => layout,
+        None => return cx.throw_type_error(format!("Unknown string layout {}", name)),
+    };
+    let encoding = text_encoding_argument(&mut cx, 3)?;
+    let width = pointer_width_argument(&mut cx, 4, handle)?;
+    match mem::read_std_string(handle, address, layout, encoding, width) {
+        Ok(value) => Ok(cx.string(&value)),
+        Err(err) => cx.throw_error(format!("{} (string at 0x{:X})", err, address)),
+    }
+}
+
+// Container elements are a type tag for values, like `read_value`, or a byte length for raw
+// buffers, like `read_memory_batch`.
+#[derive(Clone, Copy)]
+struct ElementType {
+    ty: Option<mem::ValueType>,
+    pointer: bool,
+    size: usize,
+}
+
+fn element_argument(cx: &mut FunctionContext, i: i32, handle: HANDLE) -> NeonResult<ElementType> {
+    let kind = cx.argument::<JsValue>(i)?;
+    if let Ok(size) = kind.downcast::<JsNumber, _>(cx) {
+        let size = size.value(cx) as usize;
+        if size == 0 {
+            return cx.throw_range_error("Element size must be at least 1");
+        }
+        return Ok(ElementType {
+            ty: None,
+            pointer: false,
+            size,
+        });
+    }
+    let (ty, pointer) = type_tag_argument(cx, i, handle)?;
+    Ok(ElementType {
+        ty: Some(ty),
+        pointer,
+        size: ty.size(),
+    })
+}
+
+fn element_to_js<'a>(
+    cx: &mut FunctionContext<'a>,
+    element: ElementType,
+    bytes: &[u8],
+) -> JsResult<'a, JsValue> {
+    match element.ty {
+        Some(ty) if element.pointer => {
+            Ok(cx.number(mem::Value::decode(ty, bytes).as_f64()).upcast())
+        }
+        Some(ty) => value_to_js(cx, mem::Value::decode(ty, bytes)),
+        None => Ok(JsBuffer::external(cx, bytes.to_vec()).upcast()),
+    }
+}
+
+fn elements_to_js<'a>(
+    cx: &mut FunctionContext<'a>,
+    element: ElementType,
+    bytes: &[u8],
+) -> JsResult<'a, JsArray> {
+    let array = cx.empty_array();
+    for (i, bytes) in bytes.chunks_exact(element.size).enumerate() {
+        let value = element_to_js(cx, element, bytes)?;
+        array.set(cx, i as u32, value)?;
+    }
+    Ok(array)
+}
+
+fn read_vector(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let address = address_argument(&mut cx, 1, handle)?;
+    let element = element_argument(&mut cx, 2, handle)?;
+    let width = pointer_width_argument(&mut cx, 3, handle)?;
+    match mem::read_vector(handle, address, element.size, width) {
+        Ok(bytes) => elements_to_js(&mut cx, element, &bytes),
+        Err(err) => cx.throw_error(format!("{} (vector at 0x{:X})", err, address)),
+    }
+}
+
+fn read_pascal_string(mut cx: FunctionContext) -> JsResult<JsString> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let address = address_argument(&mut cx, 1, handle)?;
+    let prefix = cx.argument::<JsNumber>(2)?.value(&mut cx) as usize;
+    if ![1, 2, 4].contains(&prefix) {
+        return cx.throw_range_error("Length prefix must be 1, 2 or 4 bytes");
+    }
+    let encoding = text_encoding_argument(&mut cx, 3)?;
+    match mem::read_pascal_string(handle, address, prefix, encoding) {
+        Ok(value) => Ok(cx.string(&value)),
+        Err(err) => cx.throw_error(format!("{} (string at 0x{:X})", err, address)),
+    }
+}
+
+fn read_clr_string(mut cx: FunctionContext) -> JsResult<JsString> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let object = address_argument(&mut cx, 1, handle)?;
+    let width = pointer_width_argument(&mut cx, 2, handle)?;
+    match mem::read_clr_string(handle, object, width) {
+        Ok(value) => Ok(cx.string(&value)),
+        Err(err) => cx.throw_error(format!("{} (string at 0x{:X})", err, object)),
+    }
+}
+
+fn read_clr_array(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let object = address_argument(&mut cx, 1, handle)?;
+    let element = element_argument(&mut cx, 2, handle)?;
+    let width = pointer_width_argument(&mut cx, 3, handle)?;
+    match mem::read_clr_array(handle, object, element.size, width) {
+        Ok(bytes) => elements_to_js(&mut cx, element, &bytes),
+        Err(err) => cx.throw_error(format!("{} (array at 0x{:X})", err, object)),
+    }
+}
+
+fn read_clr_list(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let object = address_argument(&mut cx, 1, handle)?;
+    let element = element_argument(&mut cx, 2, handle)?;
+    let width = pointer_width_argument(&mut cx, 3, handle)?;
+    match mem::read_clr_list(handle, object, element.size, width) {
+        Ok(bytes) => elements_to_js(&mut cx, element, &bytes),
+        Err(err) => cx.throw_error(format!("{} (list at 0x{:X})", err, object)),
+    }
+}
+
+// Returns `[key, value]` pairs. Object references ('pointer') affect the entry layout, so
+// reference keys and values must be given as 'pointer' rather than a same-sized integer.
+fn read_clr_dictionary(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let object = address_argument(&mut cx, 1, handle)?;
+    let key = element_argument(&mut cx, 2, handle)?;
+    let value = element_argument(&mut cx, 3, handle)?;
+    let width = pointer_width_argument(&mut cx, 4, handle)?;
+    let field = |element: ElementType| mem::ClrField {
+        size: element.size,
+        reference: element.pointer,
+    };
+    let entries = match mem::read_clr_dictionary(handle, object, field(key), field(value), width) {
+        Ok(entries) => entries,
+        Err(err) => return cx.throw_error(format!("{} (dictionary at 0x{:X})", err, object)),
+    };
+
+    let array = cx.empty_array();
+    for (i, (key_bytes, value_bytes)) in entries.into_iter().enumerate() {
+        let pair = cx.empty_array();
+        let key = element_to_js(&mut cx, key, &key_bytes)?;
+        pair.set(&mut cx, 0, key)?;
+        let value = element_to_js(&mut cx, value, &value_bytes)?;
+        pair.set(&mut cx, 1, value)?;
+        array.set(&mut cx, i as u32, pair)?;
+    }
+    Ok(array)
 }
 
 fn get_process_path(mut cx: FunctionContext) -> JsResult<JsValue> {
@@ -279,24 +1833,210 @@ fn get_process_modules(mut cx: FunctionContext) -> JsResult<JsArray> {
     Ok(array)
 }
 
+// Overrides use the camelCase field names, e.g. `{ classFields: 0x98 }`. 64-bit processes
+// default to the Unity x64 layout; 32-bit processes have no defaults and need every offset.
+// Without an object the runtime picks its defaults. Offsets missing from the object come from
+// the 64-bit defaults, which are meaningless for 32-bit processes.
+fn mono_offsets_argument(
+    cx: &mut FunctionContext,
+    i: i32,
+    width: mem::PointerWidth,
+) -> NeonResult<Option<mem::MonoOffsets>> {
+    let mut offsets = mem::MonoOffsets::unity_x64();
+    let object = match cx.argument_opt(i) {
+        Some(object) if object.is_a::<JsObject, _>(cx) => {
+            object.downcast_or_throw::<JsObject, _>(cx)?
+        }
+        _ => return Ok(None),
+    };
+    let fields = [
+        ("domainAssemblies", &mut offsets.domain_assemblies),
+        ("assemblyName", &mut offsets.assembly_name),
+        ("assemblyImage", &mut offsets.assembly_image),
+        ("imageClassCache", &mut offsets.image_class_cache),
+        ("hashTableSize", &mut offsets.hash_table_size),
+        ("hashTableTable", &mut offsets.hash_table_table),
+        ("classParent", &mut offsets.class_parent),
+        ("className", &mut offsets.class_name),
+        ("classNamespace", &mut offsets.class_namespace),
+        ("classVtableSize", &mut offsets.class_vtable_size),
+        ("classFields", &mut offsets.class_fields),
+        ("classRuntimeInfo", &mut offsets.class_runtime_info),
+        ("classFieldCount", &mut offsets.class_field_count),
+        ("classNextCache", &mut offsets.class_next_cache),
+        ("fieldSize", &mut offsets.field_size),
+        ("fieldType", &mut offsets.field_type),
+        ("fieldName", &mut offsets.field_name),
+        ("fieldOffset", &mut offsets.field_offset),
+        ("typeAttrs", &mut offsets.type_attrs),
+        ("runtimeInfoVtables", &mut offsets.runtime_info_vtables),
+        ("vtableData", &mut offsets.vtable_data),
+    ];
+    for (name, offset) in fields {
+        match object.get_opt::<JsNumber, _, _>(cx, name)? {
+            Some(value) => *offset = value.value(cx) as u64,
+            None if width == mem::PointerWidth::U32 => {
+                return cx.throw_error(format!("Missing Mono offset {}", name))
+            }
+            None => {}
+        }
+    }
+    Ok(Some(offsets))
+}
+
+fn mono_open(mut cx: FunctionContext) -> JsResult<JsBox<mem::MonoRuntime>> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let process_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let offsets = mono_offsets_argument(&mut cx, 2, mem::PointerWidth::of_process(handle))?;
+    match mem::open_mono(handle, process_id, offsets) {
+        Ok(runtime) => Ok(cx.boxed(runtime)),
+        Err(err) => cx.throw_error(err.to_string()),
+    }
+}
+
+fn mono_assemblies(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let runtime = **cx.argument::<JsBox<mem::MonoRuntime>>(1)?;
+    let array = cx.empty_array();
+    for (i, assembly) in mem::mono_assemblies(handle, &runtime)
+        .into_iter()
+        .enumerate()
+    {
+        let object = cx.empty_object();
+        let name = cx.string(&assembly.name);
+        object.set(&mut cx, "name", name)?;
+        let image = cx.number(assembly.image as f64);
+        object.set(&mut cx, "image", image)?;
+        array.set(&mut cx, i as u32, object)?;
+    }
+    Ok(array)
+}
+
+fn mono_class_to_js<'a>(
+    cx: &mut FunctionContext<'a>,
+    class: mem::MonoClass,
+) -> JsResult<'a, JsObject> {
+    let object = cx.empty_object();
+    let address = cx.number(class.address as f64);
+    object.set(cx, "address", address)?;
+    let namespace = cx.string(&class.namespace);
+    object.set(cx, "namespace", namespace)?;
+    let name = cx.string(&class.name);
+    object.set(cx, "name", name)?;
+    let parent = cx.number(class.parent as f64);
+    object.set(cx, "parent", parent)?;
+    Ok(object)
+}
+
+fn mono_classes(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let runtime = **cx.argument::<JsBox<mem::MonoRuntime>>(1)?;
+    let image = address_argument(&mut cx, 2, handle)?;
+    let array = cx.empty_array();
+    for (i, class) in mem::mono_classes(handle, &runtime, image)
+        .into_iter()
+        .enumerate()
+    {
+        let object = mono_class_to_js(&mut cx, class)?;
+        array.set(&mut cx, i as u32, object)?;
+    }
+    Ok(array)
+}
+
+fn mono_find_class(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let runtime = **cx.argument::<JsBox<mem::MonoRuntime>>(1)?;
+    let namespace = cx.argument::<JsString>(2)?.value(&mut cx);
+    let name = cx.argument::<JsString>(3)?.value(&mut cx);
+    match mem::mono_find_class(handle, &runtime, &namespace, &name) {
+        Some(class) => Ok(mono_class_to_js(&mut cx, class)?.upcast()),
+        None => Ok(cx.undefined().upcast()),
+    }
+}
+
+fn mono_class_fields(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let handle = HANDLE(cx.argument::<JsNumber>(0)?.value(&mut cx) as isize);
+    let runtime = **cx.argument::<JsBox<mem::MonoRuntime>>(1)?;
+    let class = address_argument(&mut cx, 2, handle)?;
+    let array = cx.empty_array();
+    for (i, field) in mem::mono_class_fields(handle, &runtime, class)
+        .into_iter()
+        .enumerate()
+    {
+        let object = cx.empty_object();
+        let name = cx.string(&field.name);
+        object.set(&mut cx, "name", name)?;
+        let offset = cx.number(field.offset);
+        object.set(&mut cx, "offset", offset)?;
+        let is_static = cx.boolean(field.is_static);
+        object.set(&mut cx, "static", is_static)?;
+        if let Some(address) = field.address {
+            let address = cx.number(address as f64);
+            object.set(&mut cx, "address", address)?;
+        }
+        array.set(&mut cx, i as u32, object)?;
+    }
+    Ok(array)
+}
+
 #[neon::main]
 fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("open_process", open_process)?;
     cx.export_function("close_handle", close_handle)?;
+    cx.export_function("create_cancel_token", create_cancel_token)?;
+    cx.export_function("cancel_scan", cancel_scan)?;
     cx.export_function("sig_scan_sync", sig_scan_sync)?;
     cx.export_function("sig_scan", sig_scan)?;
+    cx.export_function("group_scan", group_scan)?;
     cx.export_function("sig_scan_module_sync", sig_scan_module_sync)?;
     cx.export_function("sig_scan_module", sig_scan_module)?;
+    cx.export_function("sig_scan_reverse", sig_scan_reverse)?;
+    cx.export_function("find_function_start", find_function_start)?;
+    cx.export_function("find_references", find_references)?;
+    cx.export_function("find_string_references", find_string_references)?;
+    cx.export_function("sig_scan_module_cached_sync", sig_scan_module_cached_sync)?;
+    cx.export_function("sig_scan_module_cached", sig_scan_module_cached)?;
+    cx.export_function("signature_cache_load", signature_cache_load)?;
+    cx.export_function("signature_cache_save", signature_cache_save)?;
+    cx.export_function("signature_database_load", signature_database_load)?;
+    cx.export_function("resolve_signatures", resolve_signatures)?;
+    cx.export_function("compile_signature", compile_signature)?;
+    cx.export_function("signature_info", signature_info)?;
+    cx.export_function("signature_scan_buffer", signature_scan_buffer)?;
+    cx.export_function("evaluate_address", evaluate_address)?;
+    cx.export_function("compile_schema", compile_schema)?;
+    cx.export_function("read_struct", read_struct)?;
+    cx.export_function("read_memory_batch", read_memory_batch)?;
     cx.export_function("read_memory_buffer", read_memory_buffer)?;
+    cx.export_function("read_into", read_into)?;
+    cx.export_function("read_value", read_value)?;
+    cx.export_function("resolve_pointer_chain", resolve_pointer_chain)?;
+    cx.export_function("read_memory_from_pointer", read_memory_from_pointer)?;
+    cx.export_function(
+        "read_memory_buffer_from_pointer",
+        read_memory_buffer_from_pointer,
+    )?;
+    cx.export_function("value_scan_first", value_scan_first)?;
+    cx.export_function("value_scan_next", value_scan_next)?;
+    cx.export_function("value_scan_count", value_scan_count)?;
+    cx.export_function("value_scan_results", value_scan_results)?;
+    cx.export_function("pointer_scan", pointer_scan)?;
+    cx.export_function("pointer_rescan", pointer_rescan)?;
+    cx.export_function("pointer_paths_save", pointer_paths_save)?;
+    cx.export_function("pointer_paths_load", pointer_paths_load)?;
+    cx.export_function("write_memory_batch", write_memory_batch)?;
     cx.export_function("write_memory_buffer", write_memory_buffer)?;
+    cx.export_function("write_value", write_value)?;
     cx.export_function("alloc_memory", alloc_memory)?;
     cx.export_function("read_memory_u32", read_memory_u32)?;
     cx.export_function("write_memory_u32", write_memory_u32)?;
     cx.export_function("read_memory_u64", read_memory_u64)?;
+    cx.export_function("read_memory_u64_lossy", read_memory_u64_lossy)?;
     cx.export_function("write_memory_u64", write_memory_u64)?;
     cx.export_function("read_memory_i32", read_memory_i32)?;
     cx.export_function("write_memory_i32", write_memory_i32)?;
     cx.export_function("read_memory_i64", read_memory_i64)?;
+    cx.export_function("read_memory_i64_lossy", read_memory_i64_lossy)?;
     cx.export_function("write_memory_i64", write_memory_i64)?;
     cx.export_function("read_memory_f32", read_memory_f32)?;
     cx.export_function("write_memory_f32", write_memory_f32)?;
@@ -306,7 +2046,19 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("write_memory_bool", write_memory_bool)?;
     cx.export_function("read_memory_string", read_memory_string)?;
     cx.export_function("write_memory_string", write_memory_string)?;
+    cx.export_function("read_std_string", read_std_string)?;
+    cx.export_function("read_vector", read_vector)?;
+    cx.export_function("read_pascal_string", read_pascal_string)?;
+    cx.export_function("read_clr_string", read_clr_string)?;
+    cx.export_function("read_clr_array", read_clr_array)?;
+    cx.export_function("read_clr_list", read_clr_list)?;
+    cx.export_function("read_clr_dictionary", read_clr_dictionary)?;
     cx.export_function("get_process_path", get_process_path)?;
     cx.export_function("get_process_modules", get_process_modules)?;
+    cx.export_function("mono_open", mono_open)?;
+    cx.export_function("mono_assemblies", mono_assemblies)?;
+    cx.export_function("mono_classes", mono_classes)?;
+    cx.export_function("mono_find_class", mono_find_class)?;
+    cx.export_function("mono_class_fields", mono_class_fields)?;
     Ok(())
 }
@@ -1,12 +1,51 @@
+mod batch;
+mod cache;
+mod clr;
+mod container;
+mod control;
+mod expr;
+mod groupscan;
+mod mono;
+mod pointer;
+mod pointerscan;
+mod schema;
 mod sig;
-use sig::Signature;
+mod sigdb;
+mod text;
+mod value;
+mod valuescan;
+mod xref;
+pub use batch::{BatchWriteError, BatchWriteFailure};
+pub use cache::{ModuleIdentity, SignatureCache};
+pub use clr::{ClrEntry, ClrField};
+pub use container::{ContainerError, StringLayout};
+pub use control::{CancelToken, ProgressThrottle, ScanAbort, ScanControl, ScanProgress};
+pub use expr::{AddressContext, AddressError, AddressExpression};
+pub use groupscan::{GroupField, GroupScan};
+pub use mono::{MonoAssembly, MonoClass, MonoError, MonoField, MonoOffsets, MonoRuntime};
+pub use pointer::{read_pointer, resolve_pointer_chain, PointerChainError, PointerWidth};
+pub use pointerscan::{
+    load_pointer_paths, save_pointer_paths, PointerMap, PointerPath, PointerScanOptions,
+};
+pub use schema::{FieldType, FieldValue, Schema, SchemaField};
+pub use sig::Signature;
+pub use sigdb::{SignatureDatabase, SignatureEntry};
+pub use text::{TextEncoding, TextError, TextOptions, TextWriteOptions};
+pub use value::{Endianness, Value, ValueType};
+pub use valuescan::{FloatOptions, FloatTolerance, ScanFilter, ValueScan};
+pub use xref::Reference;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
 
 use windows::Win32::Foundation::*;
 use windows::Win32::System::Diagnostics::Debug::{ReadProcessMemory, WriteProcessMemory};
 use windows::Win32::System::Diagnostics::ToolHelp::*;
 use windows::Win32::System::Memory::*;
 use windows::Win32::System::ProcessStatus::K32GetModuleFileNameExW;
-use windows::Win32::System::Threading::{OpenProcess, PROCESS_ALL_ACCESS};
+use windows::Win32::System::Threading::{
+    GetProcessId, IsWow64Process, OpenProcess, PROCESS_ALL_ACCESS,
+};
 
 pub fn wchar_to_string(wchar: &[u16]) -> String {
     wchar
@@ -25,7 +64,7 @@ pub struct Process {
 
 #[derive(Debug)]
 pub struct Module {
-    pub base_address: u32,
+    pub base_address: u64,
     pub size: usize,
     pub name: String,
     pub path: String,
@@ -57,7 +96,7 @@ pub fn open_process(process_name: &str) -> Result<Process, Box<dyn std::error::E
     }
 }
 
-pub fn read_memory<T>(handle: HANDLE, address: u32) -> T {
+pub fn read_memory<T>(handle: HANDLE, address: u64) -> T {
     unsafe {
         let mut val: T = std::mem::zeroed();
         let size = std::mem::size_of::<T>();
@@ -74,7 +113,7 @@ pub fn read_memory<T>(handle: HANDLE, address: u32) -> T {
     }
 }
 
-pub fn read_memory_buffer(handle: HANDLE, address: u32, size: usize) -> Vec<u8> {
+pub fn read_memory_buffer(handle: HANDLE, address: u64, size: usize) -> Vec<u8> {
     unsafe {
         let buffer: Vec<u8> = vec![0; size];
         ReadProcessMemory(
@@ -88,19 +127,316 @@ pub fn read_memory_buffer(handle: HANDLE, address: u32, size: usize) -> Vec<u8>
     }
 }
 
-pub fn read_memory_from_pointer<T>(handle: HANDLE, addresses: &Vec<u32>) -> T {
-    let size = addresses.len();
-    let mut pointer = read_memory::<u32>(handle, addresses[0]);
-    if size > 2 {
-        for i in 1..addresses.len() - 1 {
-            pointer = read_memory::<u32>(handle, pointer + addresses[i]);
+pub fn try_read_memory<T>(handle: HANDLE, address: u64) -> Option<T> {
+    unsafe {
+        let mut val: T = std::mem::zeroed();
+        let size = std::mem::size_of::<T>();
+        let mut read = 0;
+
+        let ok = ReadProcessMemory(
+            handle,
+            address as *mut _,
+            &mut val as *mut _ as *mut _,
+            size,
+            &mut read,
+        );
+
+        if ok.as_bool() && read == size {
+            Some(val)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn try_read_memory_buffer(handle: HANDLE, address: u64, size: usize) -> Option<Vec<u8>> {
+    unsafe {
+        let buffer: Vec<u8> = vec![0; size];
+        let mut read = 0;
+        let ok = ReadProcessMemory(
+            handle,
+            address as *mut _,
+            buffer.as_ptr() as *mut _,
+            size,
+            &mut read,
+        );
+        if ok.as_bool() && read == size {
+            Some(buffer)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reads into `buffer` without allocating and returns how many bytes were copied, which is
+/// less than `buffer.len()` when the read runs into unreadable memory.
+pub fn read_memory_into(handle: HANDLE, address: u64, buffer: &mut [u8]) -> usize {
+    unsafe {
+        let mut read = 0;
+        ReadProcessMemory(
+            handle,
+            address as *mut _,
+            buffer.as_mut_ptr() as *mut _,
+            buffer.len(),
+            &mut read,
+        );
+        read
+    }
+}
+
+pub fn read_value(
+    handle: HANDLE,
+    address: u64,
+    ty: ValueType,
+    endianness: Endianness,
+) -> Option<Value> {
+    let buffer = try_read_memory_buffer(handle, address, ty.size())?;
+    Some(Value::decode_endian(ty, &buffer, endianness))
+}
+
+/// Reads a NUL-terminated string in bulk chunks, stopping at `options.max_length` code units.
+pub fn read_string(
+    handle: HANDLE,
+    address: u64,
+    options: &TextOptions,
+) -> Result<String, TextError> {
+    text::read_text(address, options, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+pub fn read_std_string(
+    handle: HANDLE,
+    address: u64,
+    layout: StringLayout,
+    encoding: TextEncoding,
+    width: PointerWidth,
+) -> Result<String, ContainerError> {
+    container::read_std_string(address, layout, encoding, width, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+/// Reads the raw element bytes of a `std::vector`.
+pub fn read_vector(
+    handle: HANDLE,
+    address: u64,
+    element_size: usize,
+    width: PointerWidth,
+) -> Result<Vec<u8>, ContainerError> {
+    container::read_vector(address, element_size, width, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+pub fn read_pascal_string(
+    handle: HANDLE,
+    address: u64,
+    prefix: usize,
+    encoding: TextEncoding,
+) -> Result<String, ContainerError> {
+    container::read_pascal_string(address, prefix, encoding, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+pub fn read_clr_string(
+    handle: HANDLE,
+    object: u64,
+    width: PointerWidth,
+) -> Result<String, ContainerError> {
+    clr::read_string(object, width, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+pub fn read_clr_array(
+    handle: HANDLE,
+    object: u64,
+    element_size: usize,
+    width: PointerWidth,
+) -> Result<Vec<u8>, ContainerError> {
+    clr::read_array(object, element_size, width, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+pub fn read_clr_list(
+    handle: HANDLE,
+    object: u64,
+    element_size: usize,
+    width: PointerWidth,
+) -> Result<Vec<u8>, ContainerError> {
+    clr::read_list(object, element_size, width, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+pub fn read_clr_dictionary(
+    handle: HANDLE,
+    object: u64,
+    key: ClrField,
+    value: ClrField,
+    width: PointerWidth,
+) -> Result<Vec<ClrEntry>, ContainerError> {
+    clr::read_dictionary(object, key, value, width, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+// Unity's Mono runtime, and the name used by older Unity versions.
+const MONO_MODULES: [&str; 2] = ["mono-2.0-bdwgc.dll", "mono.dll"];
+
+/// Locates the Mono root domain of a process through the `mono_get_root_domain` export.
+/// Without `offsets`, only 64-bit `mono-2.0-bdwgc.dll` is supported; the legacy `mono.dll`
+/// and 32-bit runtimes lay their structures out differently.
+pub fn open_mono(
+    handle: HANDLE,
+    process_id: u32,
+    offsets: Option<MonoOffsets>,
+) -> Result<MonoRuntime, MonoError> {
+    let module = get_process_modules(process_id)
+        .into_iter()
+        .find(|module| {
+            MONO_MODULES
+                .iter()
+                .any(|name| module.name.eq_ignore_ascii_case(name))
+        })
+        .ok_or(MonoError::ModuleNotFound)?;
+    let width = PointerWidth::of_process(handle);
+    let offsets = match offsets {
+        Some(offsets) => offsets,
+        None if width == PointerWidth::U64 && module.name.eq_ignore_ascii_case(MONO_MODULES[0]) => {
+            MonoOffsets::unity_x64()
+        }
+        None => return Err(MonoError::OffsetsRequired),
+    };
+    let mut read = |address, size| try_read_memory_buffer(handle, address, size);
+    let function = mono::find_export(module.base_address, "mono_get_root_domain", &mut read)
+        .ok_or(MonoError::ExportNotFound)?;
+    Ok(MonoRuntime {
+        domain: mono::find_root_domain(function, width, &mut read)?,
+        width,
+        offsets,
+    })
+}
+
+pub fn mono_assemblies(handle: HANDLE, runtime: &MonoRuntime) -> Vec<MonoAssembly> {
+    runtime.assemblies(&mut |address, size| try_read_memory_buffer(handle, address, size))
+}
+
+pub fn mono_classes(handle: HANDLE, runtime: &MonoRuntime, image: u64) -> Vec<MonoClass> {
+    runtime.classes(image, &mut |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+pub fn mono_find_class(
+    handle: HANDLE,
+    runtime: &MonoRuntime,
+    namespace: &str,
+    name: &str,
+) -> Option<MonoClass> {
+    runtime.find_class(namespace, name, &mut |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+pub fn mono_class_fields(handle: HANDLE, runtime: &MonoRuntime, class: u64) -> Vec<MonoField> {
+    runtime.fields(class, &mut |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+/// Evaluates address expressions against a process. Modules are only enumerated when an
+/// expression names one, and the pointer width only queried when one dereferences, each once
+/// for every expression evaluated through this context.
+pub struct ProcessAddressContext {
+    handle: HANDLE,
+    width: Option<PointerWidth>,
+    modules: Option<Vec<Module>>,
+}
+
+impl ProcessAddressContext {
+    /// Reads pointers at the process's native width.
+    pub fn new(handle: HANDLE) -> Self {
+        Self {
+            handle,
+            width: None,
+            modules: None,
         }
     }
+}
 
-    read_memory::<T>(handle, pointer + addresses.last().unwrap())
+impl AddressContext for ProcessAddressContext {
+    fn module_base(&mut self, name: &str) -> Option<u64> {
+        let handle = self.handle;
+        let modules = self
+            .modules
+            .get_or_insert_with(|| get_process_modules(unsafe { GetProcessId(handle) }));
+        modules
+            .iter()
+            .find(|module| module.name.eq_ignore_ascii_case(name))
+            .map(|module| module.base_address)
+    }
+
+    fn read_pointer(&mut self, address: u64) -> Option<u64> {
+        let handle = self.handle;
+        let width = *self
+            .width
+            .get_or_insert_with(|| PointerWidth::of_process(handle));
+        read_pointer(handle, address, width)
+    }
+}
+
+/// Reads many `(address, size)` entries in one call; unreadable entries are `None`.
+pub fn read_memory_batch(handle: HANDLE, entries: &[(u64, usize)]) -> Vec<Option<Vec<u8>>> {
+    batch::read_batch(entries, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
+}
+
+/// Reads a value at the end of a pointer chain, returning it with the final resolved address.
+pub fn read_memory_from_pointer(
+    handle: HANDLE,
+    addresses: &[u64],
+    ty: ValueType,
+    width: PointerWidth,
+) -> Result<(Value, u64), PointerChainError> {
+    let (buffer, address) = read_memory_buffer_from_pointer(handle, addresses, ty.size(), width)?;
+    Ok((Value::decode(ty, &buffer), address))
+}
+
+pub fn read_memory_buffer_from_pointer(
+    handle: HANDLE,
+    addresses: &[u64],
+    size: usize,
+    width: PointerWidth,
+) -> Result<(Vec<u8>, u64), PointerChainError> {
+    let address = resolve_pointer_chain(handle, addresses, width)?;
+    let buffer = try_read_memory_buffer(handle, address, size).ok_or(PointerChainError {
+        hop: addresses.len().saturating_sub(1),
+        address,
+    })?;
+    Ok((buffer, address))
 }
 
-pub fn write_memory<T>(handle: HANDLE, address: u32, val: T) {
+/// Reads a struct with one bulk read of the range its schema covers, following pointer
+/// fields up to `depth` levels. Returns `None` if the struct itself cannot be read.
+pub fn read_struct(
+    handle: HANDLE,
+    address: u64,
+    schema: &Schema,
+    depth: u32,
+    width: PointerWidth,
+) -> Option<FieldValue> {
+    let buffer = try_read_memory_buffer(handle, address, schema.size(width))?;
+    Some(schema.decode(&buffer, width, depth, &mut |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    }))
+}
+
+pub fn write_memory<T>(handle: HANDLE, address: u64, val: T) {
     let size = std::mem::size_of::<T>();
     unsafe {
         WriteProcessMemory(
@@ -113,7 +449,7 @@ pub fn write_memory<T>(handle: HANDLE, address: u32, val: T) {
     }
 }
 
-pub fn write_memory_buffer(handle: HANDLE, address: u32, buffer: &Vec<u8>) {
+pub fn write_memory_buffer(handle: HANDLE, address: u64, buffer: &Vec<u8>) {
     unsafe {
         let size = buffer.len();
         WriteProcessMemory(
@@ -126,7 +462,76 @@ pub fn write_memory_buffer(handle: HANDLE, address: u32, buffer: &Vec<u8>) {
     }
 }
 
-pub fn alloc_memory(handle: HANDLE, size: usize) -> u32 {
+pub fn try_write_memory_buffer(handle: HANDLE, address: u64, buffer: &[u8]) -> bool {
+    unsafe {
+        let mut written = 0;
+        let ok = WriteProcessMemory(
+            handle,
+            address as *mut _,
+            buffer.as_ptr() as *mut _,
+            buffer.len(),
+            &mut written,
+        );
+        ok.as_bool() && written == buffer.len()
+    }
+}
+
+pub fn write_value(handle: HANDLE, address: u64, value: Value, endianness: Endianness) -> bool {
+    try_write_memory_buffer(handle, address, &value.to_bytes(endianness))
+}
+
+/// Writes `text` encoded per `options`, writing nothing if it does not fit `options.capacity`.
+pub fn write_string(
+    handle: HANDLE,
+    address: u64,
+    text: &str,
+    options: &TextWriteOptions,
+) -> Result<(), TextError> {
+    let bytes = text::encode_text(text, options)?;
+    if try_write_memory_buffer(handle, address, &bytes) {
+        Ok(())
+    } else {
+        Err(TextError::WriteFailed)
+    }
+}
+
+/// Whether every byte of `[address, address + size)` is committed, writable memory.
+pub fn is_writable_range(handle: HANDLE, address: u64, size: usize) -> bool {
+    let end = match address.checked_add(size as u64) {
+        Some(end) => end,
+        None => return false,
+    };
+    let mut cursor = address;
+    for region in memory_regions(handle, address) {
+        if region.base_address > cursor || !region.writable {
+            return false;
+        }
+        cursor = match region.base_address.checked_add(region.size as u64) {
+            Some(cursor) => cursor,
+            None => return true,
+        };
+        if cursor >= end {
+            return true;
+        }
+    }
+    false
+}
+
+/// Writes all entries or none: destinations are checked and backed up before anything is
+/// written, and the backups are restored if a write fails.
+pub fn write_memory_batch(
+    handle: HANDLE,
+    entries: &[(u64, Vec<u8>)],
+) -> Result<(), BatchWriteError> {
+    batch::write_batch(
+        entries,
+        |address, size| is_writable_range(handle, address, size),
+        |address, size| try_read_memory_buffer(handle, address, size),
+        |address, bytes| try_write_memory_buffer(handle, address, bytes),
+    )
+}
+
+pub fn alloc_memory(handle: HANDLE, size: usize) -> u64 {
     let address = unsafe {
         VirtualAllocEx(
             handle,
@@ -137,59 +542,616 @@ pub fn alloc_memory(handle: HANDLE, size: usize) -> u32 {
         )
     };
 
-    let a = format!("{:?}", address);
-    u32::from_str_radix(&a[2..], 16).unwrap()
+    address as u64
+}
+
+pub fn is_64bit_process(handle: HANDLE) -> bool {
+    // A 32-bit build can only be attached to 32-bit processes.
+    if cfg!(target_pointer_width = "32") {
+        return false;
+    }
+    let mut wow64 = BOOL(0);
+    unsafe {
+        IsWow64Process(handle, &mut wow64);
+    }
+    !wow64.as_bool()
 }
 
 pub fn close_handle(handle: HANDLE) -> bool {
     unsafe { CloseHandle(handle).as_bool() }
 }
 
-pub fn sig_scan(handle: HANDLE, pattern: &str, start_address: u32) -> Option<u32> {
-    let mut info: MEMORY_BASIC_INFORMATION = unsafe { std::mem::zeroed() };
-    let size = std::mem::size_of::<MEMORY_BASIC_INFORMATION>() as usize;
-    let mut address = start_address as u64;
-    let sig = Signature::new(pattern);
-    loop {
-        unsafe {
-            if VirtualQueryEx(handle, address as *mut _, &mut info, size) != size {
-                return None;
+pub fn sig_scan(handle: HANDLE, sig: &Signature, start_address: u64) -> Option<u64> {
+    sig_scan_with_control(handle, sig, start_address, &ScanControl::default(), |_| {})
+        .unwrap_or(None)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryRegion {
+    pub base_address: u64,
+    pub size: usize,
+    pub image: bool,
+    pub readable: bool,
+    pub writable: bool,
+}
+
+/// Walks the committed regions of a process in address order, starting at the region containing `address`.
+pub struct MemoryRegions {
+    handle: HANDLE,
+    address: u64,
+}
+
+impl Iterator for MemoryRegions {
+    type Item = MemoryRegion;
+
+    fn next(&mut self) -> Option<MemoryRegion> {
+        let mut info: MEMORY_BASIC_INFORMATION = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<MEMORY_BASIC_INFORMATION>() as usize;
+        loop {
+            unsafe {
+                if VirtualQueryEx(self.handle, self.address as *mut _, &mut info, size) != size {
+                    return None;
+                }
             }
-            address = info.BaseAddress as u64 + info.RegionSize as u64;
-            if info.State != MEM_COMMIT || info.Type == MEM_IMAGE {
+            self.address = info.BaseAddress as u64 + info.RegionSize as u64;
+            if info.State != MEM_COMMIT {
                 continue;
             }
-            let buffer = read_memory_buffer(handle, info.BaseAddress as u32, info.RegionSize);
-            match sig.scan(&buffer) {
-                Some(x) => {
-                    return Some(info.BaseAddress as u32 + x);
-                }
-                None => {}
-            };
+            let protect = info.Protect.0;
+            return Some(MemoryRegion {
+                base_address: info.BaseAddress as u64,
+                size: info.RegionSize,
+                image: info.Type == MEM_IMAGE,
+                readable: protect & (PAGE_NOACCESS.0 | PAGE_GUARD.0) == 0,
+                writable: protect
+                    & (PAGE_READWRITE.0
+                        | PAGE_WRITECOPY.0
+                        | PAGE_EXECUTE_READWRITE.0
+                        | PAGE_EXECUTE_WRITECOPY.0)
+                    != 0,
+            });
+        }
+    }
+}
+
+pub fn memory_regions(handle: HANDLE, start_address: u64) -> MemoryRegions {
+    MemoryRegions {
+        handle,
+        address: start_address,
+    }
+}
+
+pub fn sig_scan_with_control<F>(
+    handle: HANDLE,
+    sig: &Signature,
+    start_address: u64,
+    control: &ScanControl,
+    mut on_progress: F,
+) -> Result<Option<u64>, ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
+    let mut progress = ScanProgress::default();
+    for region in memory_regions(handle, start_address).filter(|region| !region.image) {
+        control.check()?;
+        let buffer = read_memory_buffer(handle, region.base_address, region.size);
+        let result = sig.scan(&buffer);
+        progress.bytes_scanned += region.size as u64;
+        progress.regions_scanned += 1;
+        on_progress(progress);
+        if let Some(x) = result {
+            return Ok(Some(region.base_address + x as u64));
+        }
+    }
+    Ok(None)
+}
+
+/// Finds every base address at which all fields of `group` match, walking the same
+/// non-image regions as `sig_scan`.
+pub fn group_scan_with_control<F>(
+    handle: HANDLE,
+    group: &GroupScan,
+    start_address: u64,
+    alignment: usize,
+    control: &ScanControl,
+    mut on_progress: F,
+) -> Result<Vec<u64>, ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
+    let mut results = Vec::new();
+    let mut progress = ScanProgress::default();
+    let regions =
+        memory_regions(handle, start_address).filter(|region| region.readable && !region.image);
+    for region in regions {
+        control.check()?;
+        if let Some(buffer) = try_read_memory_buffer(handle, region.base_address, region.size) {
+            for offset in group.scan_all(&buffer, alignment) {
+                results.push(region.base_address + offset as u64);
+            }
+        }
+        progress.bytes_scanned += region.size as u64;
+        progress.regions_scanned += 1;
+        on_progress(progress);
+    }
+    Ok(results)
+}
+
+/// Builds a reverse pointer map of every readable region, keeping only values that point
+/// into readable memory.
+pub fn build_pointer_map<F>(
+    handle: HANDLE,
+    width: PointerWidth,
+    control: &ScanControl,
+    mut on_progress: F,
+) -> Result<PointerMap, ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
+    let regions: Vec<MemoryRegion> = memory_regions(handle, 0)
+        .filter(|region| region.readable)
+        .collect();
+    let is_valid = |value: u64| {
+        let i = regions.partition_point(|region| region.base_address <= value);
+        i > 0 && value - regions[i - 1].base_address < regions[i - 1].size as u64
+    };
+    let mut map = PointerMap::default();
+    let mut progress = ScanProgress::default();
+    for region in &regions {
+        control.check()?;
+        if let Some(buffer) = try_read_memory_buffer(handle, region.base_address, region.size) {
+            map.add_region(region.base_address, &buffer, width, is_valid);
+        }
+        progress.bytes_scanned += region.size as u64;
+        progress.regions_scanned += 1;
+        on_progress(progress);
+    }
+    map.finish();
+    Ok(map)
+}
+
+/// Finds static pointer paths from any module of the process to `target`.
+pub fn pointer_scan<F>(
+    handle: HANDLE,
+    process_id: u32,
+    target: u64,
+    options: &PointerScanOptions,
+    control: &ScanControl,
+    on_progress: F,
+) -> Result<Vec<PointerPath>, ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
+    let width = PointerWidth::of_process(handle);
+    let map = build_pointer_map(handle, width, control, on_progress)?;
+    map.scan(target, &get_process_modules(process_id), options, control)
+}
+
+pub fn resolve_pointer_path(
+    handle: HANDLE,
+    modules: &[Module],
+    path: &PointerPath,
+    width: PointerWidth,
+) -> Option<u64> {
+    let module = modules
+        .iter()
+        .find(|module| module.name.eq_ignore_ascii_case(&path.module))?;
+    let mut address = module.base_address + path.base_offset;
+    for offset in &path.offsets {
+        address = read_pointer(handle, address, width)?.wrapping_add(*offset);
+    }
+    Some(address)
+}
+
+/// Keeps the paths that still lead to `target`, typically after the game was restarted.
+pub fn rescan_pointer_paths(
+    handle: HANDLE,
+    process_id: u32,
+    paths: &[PointerPath],
+    target: u64,
+) -> Vec<PointerPath> {
+    let modules = get_process_modules(process_id);
+    let width = PointerWidth::of_process(handle);
+    paths
+        .iter()
+        .filter(|path| resolve_pointer_path(handle, &modules, path, width) == Some(target))
+        .cloned()
+        .collect()
+}
+
+/// Starts a value scan over every writable region. Relative filters need a previous scan
+/// and must go through `next_value_scan` instead.
+pub fn first_value_scan<F>(
+    handle: HANDLE,
+    ty: ValueType,
+    filter: &ScanFilter,
+    float: &FloatOptions,
+    control: &ScanControl,
+    mut on_progress: F,
+) -> Result<ValueScan, ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
+    let mut scan = ValueScan::new(ty, filter);
+    let mut progress = ScanProgress::default();
+    for region in memory_regions(handle, 0).filter(|region| region.readable && region.writable) {
+        control.check()?;
+        if let Some(buffer) = try_read_memory_buffer(handle, region.base_address, region.size) {
+            scan.add_region(region.base_address, buffer, filter, float);
         }
+        progress.bytes_scanned += region.size as u64;
+        progress.regions_scanned += 1;
+        on_progress(progress);
     }
+    Ok(scan)
+}
+
+pub fn next_value_scan<F>(
+    handle: HANDLE,
+    scan: &mut ValueScan,
+    filter: &ScanFilter,
+    float: &FloatOptions,
+    control: &ScanControl,
+    on_progress: F,
+) -> Result<(), ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
+    scan.next_scan(filter, float, control, on_progress, |address, size| {
+        try_read_memory_buffer(handle, address, size)
+    })
 }
 
 pub fn sig_scan_module(
     handle: HANDLE,
     process_id: u32,
-    pattern: &str,
+    sig: &Signature,
     module_name: &str,
-) -> Option<u32> {
+) -> Option<u64> {
+    sig_scan_module_with_control(
+        handle,
+        process_id,
+        sig,
+        module_name,
+        &ScanControl::default(),
+        |_| {},
+    )
+    .unwrap_or(None)
+}
+
+// Modules are scanned in chunks so cancellation and progress are not limited to one check per module.
+const MODULE_SCAN_CHUNK: usize = 0x100000;
+
+pub fn sig_scan_module_with_control<F>(
+    handle: HANDLE,
+    process_id: u32,
+    sig: &Signature,
+    module_name: &str,
+    control: &ScanControl,
+    on_progress: F,
+) -> Result<Option<u64>, ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
     let modules = get_process_modules(process_id);
-    for module in modules {
-        if module.name == module_name {
-            let sig = Signature::new(pattern);
-            let buffer = read_memory_buffer(handle, module.base_address, module.size);
-            match sig.scan(&buffer) {
-                Some(x) => {
-                    return Some(module.base_address + x);
-                }
-                None => return None,
+    match modules.iter().find(|module| module.name == module_name) {
+        Some(module) => scan_module(handle, module, sig, control, on_progress),
+        None => Ok(None),
+    }
+}
+
+fn scan_module<F>(
+    handle: HANDLE,
+    module: &Module,
+    sig: &Signature,
+    control: &ScanControl,
+    mut on_progress: F,
+) -> Result<Option<u64>, ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
+    let buffer = read_memory_buffer(handle, module.base_address, module.size);
+    let mut progress = ScanProgress::default();
+    let mut offset = 0;
+    while offset < buffer.len() {
+        control.check()?;
+        // Overlap the next chunk so matches straddling a chunk boundary are still found.
+        let end = (offset + MODULE_SCAN_CHUNK + sig.value.len() - 1).min(buffer.len());
+        if let Some(x) = sig.scan(&buffer[offset..end]) {
+            return Ok(Some(module.base_address + (offset + x as usize) as u64));
+        }
+        let scanned = MODULE_SCAN_CHUNK.min(buffer.len() - offset);
+        offset += scanned;
+        progress.bytes_scanned += scanned as u64;
+        if offset >= buffer.len() {
+            progress.regions_scanned = 1;
+        }
+        on_progress(progress);
+    }
+    Ok(None)
+}
+
+/// Like `sig_scan_module`, but reuses the module-relative offset found for the same build of
+/// the module, identified by a hash of its file, so a cache hit reads no process memory. The
+/// cache is locked only around lookups and inserts, not while hashing or scanning.
+pub fn sig_scan_module_cached(
+    handle: HANDLE,
+    process_id: u32,
+    sig: &Signature,
+    module_name: &str,
+    cache: &Mutex<SignatureCache>,
+) -> Option<u64> {
+    let modules = get_process_modules(process_id);
+    let module = modules.iter().find(|module| module.name == module_name)?;
+    scan_module_cached(handle, module, sig, cache)
+}
+
+fn scan_module_cached(
+    handle: HANDLE,
+    module: &Module,
+    sig: &Signature,
+    cache: &Mutex<SignatureCache>,
+) -> Option<u64> {
+    let identity = ModuleIdentity::new(module, cache);
+    if let Some(identity) = &identity {
+        if let Some(offset) = cache.lock().unwrap().get(identity, sig) {
+            return Some(module.base_address + offset as u64);
+        }
+    }
+    let image = read_memory_buffer(handle, module.base_address, module.size);
+    let offset = sig.scan(&image)?;
+    if let Some(identity) = identity {
+        cache.lock().unwrap().insert(identity, sig, offset);
+    }
+    Some(module.base_address + offset as u64)
+}
+
+#[derive(Debug)]
+pub struct SignatureResolution {
+    pub name: String,
+    /// Version of the entry that was resolved, or of the last one tried if none resolved.
+    pub version: Option<String>,
+    pub result: Result<u64, String>,
+}
+
+fn resolve_signature(
+    handle: HANDLE,
+    modules: &[Module],
+    entry: &SignatureEntry,
+    width: PointerWidth,
+    cache: Option<&Mutex<SignatureCache>>,
+) -> Result<u64, String> {
+    let found = match &entry.module {
+        Some(module_name) => {
+            let module = match modules.iter().find(|module| &module.name == module_name) {
+                Some(module) => module,
+                None => return Err(format!("Module {} not found", module_name)),
             };
+            match cache {
+                Some(cache) => scan_module_cached(handle, module, &entry.pattern, cache),
+                None => scan_module(
+                    handle,
+                    module,
+                    &entry.pattern,
+                    &ScanControl::default(),
+                    |_| {},
+                )
+                .unwrap_or(None),
+            }
+        }
+        None => sig_scan(handle, &entry.pattern, 0),
+    };
+    match found {
+        Some(found) => entry.resolve(found, width, |address, size| {
+            try_read_memory_buffer(handle, address, size)
+        }),
+        None => Err("Pattern not found".into()),
+    }
+}
+
+/// Resolves each signature name once. With a `version`, a name uses its entry for that version,
+/// or else its unversioned entry, and names with neither are skipped. Without a version, the
+/// entries of a name are tried in file order and the first that resolves is used.
+pub fn resolve_signatures(
+    handle: HANDLE,
+    process_id: u32,
+    database: &SignatureDatabase,
+    version: Option<&str>,
+    cache: Option<&Mutex<SignatureCache>>,
+) -> Vec<SignatureResolution> {
+    let modules = get_process_modules(process_id);
+    let width = PointerWidth::of_process(handle);
+    let mut names: Vec<&str> = Vec::new();
+    for entry in &database.entries {
+        if !names.contains(&entry.name.as_str()) {
+            names.push(&entry.name);
+        }
+    }
+    let mut resolutions = Vec::new();
+    for name in names {
+        let entries = database.entries.iter().filter(|entry| entry.name == name);
+        let candidates: Vec<&SignatureEntry> = match version {
+            Some(version) => {
+                let entries: Vec<_> = entries.collect();
+                entries
+                    .iter()
+                    .find(|entry| entry.version.as_deref() == Some(version))
+                    .or_else(|| entries.iter().find(|entry| entry.version.is_none()))
+                    .copied()
+                    .into_iter()
+                    .collect()
+            }
+            None => entries.collect(),
+        };
+        let mut resolution = None;
+        for entry in candidates {
+            let result = resolve_signature(handle, &modules, entry, width, cache);
+            let resolved = result.is_ok();
+            resolution = Some(SignatureResolution {
+                name: name.to_string(),
+                version: entry.version.clone(),
+                result,
+            });
+            if resolved {
+                break;
+            }
+        }
+        resolutions.extend(resolution);
+    }
+    resolutions
+}
+
+pub fn find_module_containing(process_id: u32, address: u64) -> Option<Module> {
+    get_process_modules(process_id).into_iter().find(|module| {
+        address >= module.base_address && address - module.base_address < module.size as u64
+    })
+}
+
+/// Searches backward from `address` for the closest match starting at or before it,
+/// without leaving the module that contains `address`.
+pub fn sig_scan_reverse(
+    handle: HANDLE,
+    process_id: u32,
+    sig: &Signature,
+    address: u64,
+) -> Option<u64> {
+    let module = find_module_containing(process_id, address)?;
+    let end = (address + sig.len() as u64).min(module.base_address + module.size as u64);
+    sig::search_backward(
+        module.base_address,
+        end,
+        sig.len().saturating_sub(1),
+        |address, size| try_read_memory_buffer(handle, address, size),
+        |chunk, _| sig.scan_reverse(chunk).map(|offset| offset as usize),
+    )
+}
+
+/// Heuristically finds the start of the function containing `address`, see
+/// `xref::last_function_start`.
+pub fn find_function_start(handle: HANDLE, process_id: u32, address: u64) -> Option<u64> {
+    let module = find_module_containing(process_id, address)?;
+    let is_64bit = is_64bit_process(handle);
+    let end = (address + 3).min(module.base_address + module.size as u64);
+    sig::search_backward(
+        module.base_address,
+        end,
+        xref::FUNCTION_START_CONTEXT - 1,
+        |address, size| try_read_memory_buffer(handle, address, size),
+        |chunk, base| xref::last_function_start(chunk, base, is_64bit),
+    )
+}
+
+pub fn find_module_references(
+    handle: HANDLE,
+    process_id: u32,
+    module_name: &str,
+    target: u64,
+) -> Option<Vec<Reference>> {
+    let modules = get_process_modules(process_id);
+    let module = modules.iter().find(|module| module.name == module_name)?;
+    let buffer = read_memory_buffer(handle, module.base_address, module.size);
+    Some(xref::find_references(
+        &buffer,
+        module.base_address,
+        target,
+        is_64bit_process(handle),
+    ))
+}
+
+pub fn find_process_references<F>(
+    handle: HANDLE,
+    target: u64,
+    control: &ScanControl,
+    mut on_progress: F,
+) -> Result<Vec<Reference>, ScanAbort>
+where
+    F: FnMut(ScanProgress),
+{
+    let is_64bit = is_64bit_process(handle);
+    let mut references = Vec::new();
+    let mut progress = ScanProgress::default();
+    for region in memory_regions(handle, 0).filter(|region| region.readable) {
+        control.check()?;
+        let buffer = read_memory_buffer(handle, region.base_address, region.size);
+        references.extend(xref::find_references(
+            &buffer,
+            region.base_address,
+            target,
+            is_64bit,
+        ));
+        progress.bytes_scanned += region.size as u64;
+        progress.regions_scanned += 1;
+        on_progress(progress);
+    }
+    Ok(references)
+}
+
+#[derive(Debug)]
+pub struct StringReference {
+    pub string_address: u64,
+    pub wide: bool,
+    pub reference: Reference,
+}
+
+/// Finds every NUL terminated occurrence of `text`, as ASCII and as UTF-16, in the named module
+/// (or in every module) and returns the code and data locations referencing those occurrences.
+/// Each module is searched for references once, whatever the number of occurrences in it.
+pub fn find_string_references(
+    handle: HANDLE,
+    process_id: u32,
+    module_name: Option<&str>,
+    text: &str,
+) -> Option<Vec<StringReference>> {
+    let modules: Vec<Module> = get_process_modules(process_id)
+        .into_iter()
+        .filter(|module| match module_name {
+            Some(name) => module.name == name,
+            None => true,
+        })
+        .collect();
+    if modules.is_empty() {
+        return None;
+    }
+    // An empty string would match every NUL byte.
+    if text.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let mut ascii = text.as_bytes().to_vec();
+    ascii.push(0);
+    let mut utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    utf16.extend_from_slice(&[0, 0]);
+    let patterns = [
+        (Signature::from_bytes(&ascii), false),
+        (Signature::from_bytes(&utf16), true),
+    ];
+
+    let is_64bit = is_64bit_process(handle);
+    let mut references = Vec::new();
+    for module in &modules {
+        let buffer = read_memory_buffer(handle, module.base_address, module.size);
+        // Occurrence addresses, and whether each is the UTF-16 form.
+        let mut strings = HashMap::new();
+        for (sig, wide) in &patterns {
+            for offset in sig.scan_all(&buffer) {
+                strings.insert(module.base_address + offset as u64, *wide);
+            }
+        }
+        if strings.is_empty() {
+            continue;
+        }
+        let is_string = |address| strings.contains_key(&address);
+        for (string_address, reference) in
+            xref::find_references_to(&buffer, module.base_address, is_string, is_64bit)
+        {
+            references.push(StringReference {
+                string_address,
+                wide: strings[&string_address],
+                reference,
+            });
         }
     }
-    None
+    Some(references)
 }
 
 pub fn get_process_path(handle: HANDLE) -> Option<String> {
@@ -212,7 +1174,7 @@ pub fn get_process_modules(process_id: u32) -> Vec<Module> {
         let mut modules = Vec::new();
         while Module32NextW(hProcessId, &mut entry).as_bool() {
             let module = Module {
-                base_address: entry.modBaseAddr as u32,
+                base_address: entry.modBaseAddr as u64,
                 size: entry.modBaseSize as usize,
                 name: wchar_to_string(&entry.szModule),
                 path: wchar_to_string(&entry.szExePath),
@@ -231,7 +1193,7 @@ fn test() {
     let now = std::time::Instant::now();
     sig_scan(
         process.handle,
-        "55 8B EC 57 56 53 83 EC 14 33 C0 89 45 E8 83 3D AD EC DF",
+        &Signature::new("55 8B EC 57 56 53 83 EC 14 33 C0 89 45 E8 83 3D AD EC DF").unwrap(),
         0,
     );
     println!("{:?}", now.elapsed().as_millis());
@@ -0,0 +1,232 @@
+// Entries closer than this are fetched with one read covering both.
+const MAX_GAP: u64 = 0x1000;
+// Upper bound for a merged read, so a sparse batch does not pull in megabytes.
+const MAX_SPAN: u64 = 0x10000;
+
+/// Reads every `(address, size)` entry through `read`, merging nearby entries into shared
+/// reads. Entries that cannot be read are `None`. If a merged read fails, its entries are
+/// retried one by one so a single bad address does not fail its neighbours. Entries that run
+/// past the end of the address space are `None` without being read.
+pub fn read_batch<F>(entries: &[(u64, usize)], mut read: F) -> Vec<Option<Vec<u8>>>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let mut results = vec![None; entries.len()];
+    let entry_end = |i: usize| entries[i].0.checked_add(entries[i].1 as u64);
+    let mut order: Vec<usize> = (0..entries.len())
+        .filter(|&i| entry_end(i).is_some())
+        .collect();
+    order.sort_by_key(|&i| entries[i].0);
+
+    let mut start = 0;
+    while start < order.len() {
+        let span_base = entries[order[start]].0;
+        let mut span_end = span_base + entries[order[start]].1 as u64;
+        let mut end = start + 1;
+        while end < order.len() {
+            let address = entries[order[end]].0;
+            let entry_end = address + entries[order[end]].1 as u64;
+            let within_gap = match span_end.checked_add(MAX_GAP) {
+                Some(limit) => address <= limit,
+                None => true,
+            };
+            if !within_gap || entry_end.max(span_end) - span_base > MAX_SPAN {
+                break;
+            }
+            span_end = span_end.max(entry_end);
+            end += 1;
+        }
+
+        let span = if end - start > 1 {
+            read(span_base, (span_end - span_base) as usize)
+        } else {
+            None
+        };
+        for &i in &order[start..end] {
+            let (address, size) = entries[i];
+            results[i] = match &span {
+                Some(buffer) => {
+                    let offset = (address - span_base) as usize;
+                    Some(buffer[offset..offset + size].to_vec())
+                }
+                None => read(address, size),
+            };
+        }
+        start = end;
+    }
+    results
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchWriteFailure {
+    NotWritable,
+    BackupFailed,
+    /// `restored` tells whether every earlier write was rolled back.
+    WriteFailed {
+        restored: bool,
+    },
+}
+
+/// The entry that stopped a batch write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchWriteError {
+    pub index: usize,
+    pub address: u64,
+    pub failure: BatchWriteFailure,
+}
+
+impl std::fmt::Display for BatchWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.failure {
+            BatchWriteFailure::NotWritable => write!(
+                f,
+                "Memory at 0x{:X} (entry {}) is not writable, nothing was written",
+                self.address, self.index
+            ),
+            BatchWriteFailure::BackupFailed => write!(
+                f,
+                "Failed to read memory at 0x{:X} (entry {}), nothing was written",
+                self.address, self.index
+            ),
+            BatchWriteFailure::WriteFailed { restored: true } => write!(
+                f,
+                "Failed to write memory at 0x{:X} (entry {}), earlier writes were restored",
+                self.address, self.index
+            ),
+            BatchWriteFailure::WriteFailed { restored: false } => write!(
+                f,
+                "Failed to write memory at 0x{:X} (entry {}) and to restore earlier writes",
+                self.address, self.index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BatchWriteError {}
+
+/// Writes every entry or none of them: all destinations are checked with `is_writable` and
+/// backed up through `read` first, and a failed `write` restores the backups in reverse order.
+pub fn write_batch<C, R, W>(
+    entries: &[(u64, Vec<u8>)],
+    is_writable: C,
+    mut read: R,
+    mut write: W,
+) -> Result<(), BatchWriteError>
+where
+    C: Fn(u64, usize) -> bool,
+    R: FnMut(u64, usize) -> Option<Vec<u8>>,
+    W: FnMut(u64, &[u8]) -> bool,
+{
+    let error = |index: usize, failure| BatchWriteError {
+        index,
+        address: entries[index].0,
+        failure,
+    };
+    if let Some(index) = entries
+        .iter()
+        .position(|(address, bytes)| !is_writable(*address, bytes.len()))
+    {
+        return Err(error(index, BatchWriteFailure::NotWritable));
+    }
+    let mut backups = Vec::with_capacity(entries.len());
+    for (index, (address, bytes)) in entries.iter().enumerate() {
+        match read(*address, bytes.len()) {
+            Some(backup) => backups.push(backup),
+            None => return Err(error(index, BatchWriteFailure::BackupFailed)),
+        }
+    }
+
+    for (index, (address, bytes)) in entries.iter().enumerate() {
+        if write(*address, bytes) {
+            continue;
+        }
+        // The failed write may have been partial, so it is restored along with the others.
+        let mut restored = true;
+        for i in (0..=index).rev() {
+            restored &= write(entries[i].0, &backups[i]);
+        }
+        return Err(error(index, BatchWriteFailure::WriteFailed { restored }));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_read_batch() {
+    let memory: Vec<u8> = (0..=255).collect();
+    let mut reads = Vec::new();
+    let results = read_batch(
+        &[(0x20, 4), (0x10, 2), (0x90000, 1), (0x80, 1)],
+        |address, size| {
+            reads.push((address, size));
+            let start = address as usize;
+            memory.get(start..start + size).map(|bytes| bytes.to_vec())
+        },
+    );
+    assert_eq!(
+        results,
+        vec![
+            Some(vec![0x20, 0x21, 0x22, 0x23]),
+            Some(vec![0x10, 0x11]),
+            None,
+            Some(vec![0x80]),
+        ]
+    );
+    assert_eq!(reads, vec![(0x10, 0x71), (0x90000, 1)]);
+
+    let mut reads = Vec::new();
+    let results = read_batch(
+        &[(u64::MAX - 1, 1), (u64::MAX, 2), (u64::MAX - 0x10, 4)],
+        |address, size| {
+            reads.push((address, size));
+            Some(vec![0; size])
+        },
+    );
+    assert_eq!(results, vec![Some(vec![0]), None, Some(vec![0; 4])]);
+    assert_eq!(reads, vec![(u64::MAX - 0x10, 0x10)]);
+}
+
+#[test]
+fn test_write_batch() {
+    let mut memory = [0u8; 0x20];
+    let entries = vec![(0x4, vec![1, 2]), (0x10, vec![3]), (0x1E, vec![4, 5])];
+    let result = write_batch(
+        &entries,
+        |address, size| address as usize + size <= 0x20,
+        |address, size| Some(memory[address as usize..address as usize + size].to_vec()),
+        |_, _| true,
+    );
+    assert_eq!(result, Ok(()));
+
+    let mut writes = 0;
+    let result = write_batch(
+        &entries,
+        |_, _| true,
+        |address, size| Some(vec![address as u8; size]),
+        |address, bytes| {
+            writes += 1;
+            if address == 0x10 && bytes == [3] {
+                return false;
+            }
+            memory[address as usize..address as usize + bytes.len()].copy_from_slice(bytes);
+            true
+        },
+    );
+    assert_eq!(
+        result,
+        Err(BatchWriteError {
+            index: 1,
+            address: 0x10,
+            failure: BatchWriteFailure::WriteFailed { restored: true },
+        })
+    );
+    assert_eq!(writes, 4);
+    assert_eq!(&memory[0x4..0x6], &[0x4, 0x4]);
+
+    let result = write_batch(
+        &entries,
+        |address, _| address != 0x1E,
+        |_, _| None,
+        |_, _| true,
+    );
+    assert_eq!(result.unwrap_err().failure, BatchWriteFailure::NotWritable);
+}
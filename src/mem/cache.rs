@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::sig::Signature;
+use super::Module;
+
+/// Identifies one build of a module by its path, image size and a hash of its file on disk, so
+/// a patched or repacked module never matches offsets found in another build.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModuleIdentity {
+    pub path: String,
+    pub size: usize,
+    pub hash: u64,
+}
+
+/// FNV-1a over the whole file.
+pub fn hash_file(path: &Path) -> io::Result<u64> {
+    let mut file = fs::File::open(path)?;
+    let mut buffer = vec![0; 0x10000];
+    let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hash);
+        }
+        for &byte in &buffer[..read] {
+            hash = (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3);
+        }
+    }
+}
+
+impl ModuleIdentity {
+    /// Hashes the file behind `module`, or reuses the hash from an earlier call while the file
+    /// keeps its length and modification time. `None` if the file cannot be read.
+    pub fn new(module: &Module, cache: &Mutex<SignatureCache>) -> Option<Self> {
+        let path = Path::new(&module.path);
+        let metadata = fs::metadata(path).ok()?;
+        let stamp = metadata
+            .modified()
+            .ok()
+            .map(|modified| (metadata.len(), modified));
+        let known = stamp.and_then(|stamp| cache.lock().unwrap().file_hash(&module.path, stamp));
+        let hash = match known {
+            Some(hash) => hash,
+            None => {
+                // Hashing can take a while, so it runs without holding the cache.
+                let hash = hash_file(path).ok()?;
+                if let Some(stamp) = stamp {
+                    let mut cache = cache.lock().unwrap();
+                    cache.file_hashes.insert(module.path.clone(), (stamp, hash));
+                }
+                hash
+            }
+        };
+        Some(Self {
+            path: module.path.clone(),
+            size: module.size,
+            hash,
+        })
+    }
+}
+
+// A file's length and modification time.
+type FileStamp = (u64, SystemTime);
+
+/// Module-relative offsets of resolved signatures, persisted as one tab separated entry per line.
+#[derive(Debug, Default)]
+pub struct SignatureCache {
+    entries: HashMap<(ModuleIdentity, String), u32>,
+    // Hashes of module files by path, only kept in memory.
+    file_hashes: HashMap<String, (FileStamp, u64)>,
+}
+
+impl SignatureCache {
+    fn file_hash(&self, path: &str, stamp: FileStamp) -> Option<u64> {
+        match self.file_hashes.get(path) {
+            Some(&(known, hash)) if known == stamp => Some(hash),
+            _ => None,
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut cache = Self::default();
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(cache),
+            Err(err) => return Err(err),
+        };
+        for line in contents.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if let [path, size, hash, pattern, offset] = fields[..] {
+                // Malformed lines are dropped, the entry will simply be scanned for again.
+                let size = match size.parse() {
+                    Ok(size) => size,
+                    Err(_) => continue,
+                };
+                let hash = match u64::from_str_radix(hash, 16) {
+                    Ok(hash) => hash,
+                    Err(_) => continue,
+                };
+                let offset = match u32::from_str_radix(offset, 16) {
+                    Ok(offset) => offset,
+                    Err(_) => continue,
+                };
+                let identity = ModuleIdentity {
+                    path: path.to_string(),
+                    size,
+                    hash,
+                };
+                cache
+                    .entries
+                    .insert((identity, pattern.to_string()), offset);
+            }
+        }
+        Ok(cache)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut contents = String::new();
+        for ((identity, pattern), offset) in &self.entries {
+            contents.push_str(&format!(
+                "{}\t{}\t{:016x}\t{}\t{:x}\n",
+                identity.path, identity.size, identity.hash, pattern, offset
+            ));
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn get(&self, identity: &ModuleIdentity, sig: &Signature) -> Option<u32> {
+        self.entries
+            .get(&(identity.clone(), sig.to_string()))
+            .copied()
+    }
+
+    pub fn insert(&mut self, identity: ModuleIdentity, sig: &Signature, offset: u32) {
+        // Entries left behind by an older build of the same module can never match again.
+        self.entries
+            .retain(|(cached, _), _| cached.path != identity.path || *cached == identity);
+        self.entries.insert((identity, sig.to_string()), offset);
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    let file = std::env::temp_dir().join("mem-tool-signature-cache-module.bin");
+    fs::write(&file, b"MZ\x90\x00").unwrap();
+    let module = Module {
+        base_address: 0x400000,
+        size: 0x10000,
+        name: "game.exe".to_string(),
+        path: file.to_string_lossy().into_owned(),
+    };
+    let cache = Mutex::new(SignatureCache::default());
+    let identity = ModuleIdentity::new(&module, &cache).unwrap();
+    assert_eq!(identity.hash, hash_file(&file).unwrap());
+
+    let sig = Signature::new("55 8B ? 90").unwrap();
+    let mut cache = cache.into_inner().unwrap();
+    cache.insert(identity.clone(), &sig, 0x1234);
+
+    let path = std::env::temp_dir().join("mem-tool-signature-cache-test.txt");
+    cache.save(&path).unwrap();
+    let loaded = SignatureCache::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.get(&identity, &sig), Some(0x1234));
+
+    // Patching a single byte of the file changes the identity.
+    fs::write(&file, b"MZ\x91\x00").unwrap();
+    let patched = ModuleIdentity {
+        hash: hash_file(&file).unwrap(),
+        ..identity.clone()
+    };
+    fs::remove_file(&file).unwrap();
+    assert_ne!(patched, identity);
+    assert_eq!(loaded.get(&patched, &sig), None);
+}
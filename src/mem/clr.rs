@@ -0,0 +1,261 @@
+// Readers for .NET Framework objects. Every object starts with its method table pointer and
+// fields follow, object references first, so offsets below are in pointer-size units.
+
+use super::container::{checked_size, read_exact, ContainerError};
+use super::pointer::PointerWidth;
+use super::text::{decode_lossy, TextEncoding};
+
+/// How a field or element is stored: a value of `size` bytes or an object reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClrField {
+    pub size: usize,
+    pub reference: bool,
+}
+
+/// The key and value bytes of a dictionary entry.
+pub type ClrEntry = (Vec<u8>, Vec<u8>);
+
+/// Offsets within a `Dictionary<K,V>` entry: `hashCode`, `next`, `key` and `value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryLayout {
+    pub hash_code: usize,
+    pub key: usize,
+    pub value: usize,
+    pub size: usize,
+}
+
+impl EntryLayout {
+    /// Blittable entries keep declaration order. Entries holding references get the runtime's
+    /// auto layout: references first, then the other fields from largest to smallest.
+    pub fn new(key: ClrField, value: ClrField) -> Self {
+        let int = ClrField {
+            size: 4,
+            reference: false,
+        };
+        let fields = [int, int, key, value];
+        let mut order = [0, 1, 2, 3];
+        if key.reference || value.reference {
+            order.sort_by_key(|&i| (!fields[i].reference, std::cmp::Reverse(fields[i].size)));
+        }
+        let mut offsets = [0; 4];
+        let mut offset = 0;
+        let mut alignment = 1;
+        for &i in &order {
+            // Fields align to their size, up to 8 bytes.
+            let align = 1 << fields[i].size.trailing_zeros().min(3);
+            offset = (offset + align - 1) & !(align - 1);
+            offsets[i] = offset;
+            offset += fields[i].size;
+            alignment = alignment.max(align);
+        }
+        Self {
+            hash_code: offsets[0],
+            key: offsets[2],
+            value: offsets[3],
+            size: (offset + alignment - 1) & !(alignment - 1),
+        }
+    }
+}
+
+fn read_length(bytes: &[u8]) -> Result<u64, ContainerError> {
+    let length = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    if length < 0 {
+        return Err(ContainerError::Corrupt);
+    }
+    Ok(length as u64)
+}
+
+/// Reads a `System.String`: a 32-bit length in UTF-16 units, then the characters.
+pub fn read_string<F>(
+    object: u64,
+    width: PointerWidth,
+    mut read: F,
+) -> Result<String, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let w = width.size();
+    let header = read_exact(&mut read, object, w + 4)?;
+    let size = checked_size(read_length(&header[w..])?, 2)?;
+    let bytes = read_exact(&mut read, object + w as u64 + 4, size)?;
+    Ok(decode_lossy(&bytes, TextEncoding::Utf16Le))
+}
+
+// Reads `count` elements of the array at `object`, or all of them without a count.
+fn read_elements<F>(
+    object: u64,
+    element_size: usize,
+    count: Option<u64>,
+    width: PointerWidth,
+    read: &mut F,
+) -> Result<Vec<u8>, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let w = width.size();
+    let header = read_exact(read, object, 2 * w)?;
+    let length = read_length(&header[w..])?;
+    let count = match count {
+        Some(count) if count > length => return Err(ContainerError::Corrupt),
+        Some(count) => count,
+        None => length,
+    };
+    let size = checked_size(count, element_size)?;
+    read_exact(read, object + 2 * w as u64, size)
+}
+
+/// Reads the element bytes of a single-dimensional array, `T[]`.
+pub fn read_array<F>(
+    object: u64,
+    element_size: usize,
+    width: PointerWidth,
+    mut read: F,
+) -> Result<Vec<u8>, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    read_elements(object, element_size, None, width, &mut read)
+}
+
+/// Reads the live elements of a `List<T>`, the first `_size` of its `_items` array.
+pub fn read_list<F>(
+    object: u64,
+    element_size: usize,
+    width: PointerWidth,
+    mut read: F,
+) -> Result<Vec<u8>, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    // `_items` and `_syncRoot`, then `_size` and `_version`.
+    let w = width.size();
+    let header = read_exact(&mut read, object, 3 * w + 4)?;
+    let items = width.decode(&header[w..]);
+    let size = read_length(&header[3 * w..])?;
+    read_elements(items, element_size, Some(size), width, &mut read)
+}
+
+/// Reads the used entries of a `Dictionary<K,V>` as `(key, value)` byte pairs.
+pub fn read_dictionary<F>(
+    object: u64,
+    key: ClrField,
+    value: ClrField,
+    width: PointerWidth,
+    mut read: F,
+) -> Result<Vec<ClrEntry>, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    // Six references (`buckets`, `entries`, `comparer`, `keys`, `values`, `_syncRoot`), then
+    // `count`, `version`, `freeList` and `freeCount`.
+    let w = width.size();
+    let header = read_exact(&mut read, object, 7 * w + 4)?;
+    let entries = width.decode(&header[2 * w..]);
+    let count = read_length(&header[7 * w..])?;
+    // `entries` stays null until the first insert.
+    if count == 0 || entries == 0 {
+        return Ok(Vec::new());
+    }
+    let layout = EntryLayout::new(key, value);
+    let bytes = read_elements(entries, layout.size, Some(count), width, &mut read)?;
+    Ok(bytes
+        .chunks_exact(layout.size)
+        // Removed entries have a hash code of -1.
+        .filter(|entry| entry[layout.hash_code + 3] & 0x80 == 0)
+        .map(|entry| {
+            (
+                entry[layout.key..layout.key + key.size].to_vec(),
+                entry[layout.value..layout.value + value.size].to_vec(),
+            )
+        })
+        .collect())
+}
+
+#[test]
+fn test_entry_layout() {
+    let int = ClrField {
+        size: 4,
+        reference: false,
+    };
+    let long = ClrField {
+        size: 8,
+        reference: false,
+    };
+    let object = ClrField {
+        size: 4,
+        reference: true,
+    };
+    assert_eq!(
+        EntryLayout::new(int, long),
+        EntryLayout {
+            hash_code: 0,
+            key: 8,
+            value: 16,
+            size: 24
+        }
+    );
+    assert_eq!(
+        EntryLayout::new(int, object),
+        EntryLayout {
+            hash_code: 4,
+            key: 12,
+            value: 0,
+            size: 16
+        }
+    );
+}
+
+#[test]
+fn test_read_clr() {
+    let mut memory = vec![0u8; 0x200];
+    let mut put = |address: usize, bytes: &[u8]| {
+        memory[address..address + bytes.len()].copy_from_slice(bytes);
+    };
+    // x86 System.String "hi".
+    put(0x10, &[0, 0, 0, 0, 2, 0, 0, 0, b'h', 0, b'i', 0]);
+    // x86 List<int> with two of its four slots used.
+    put(0x20, &[0, 0, 0, 0, 0x40, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0]);
+    put(0x40, &[0, 0, 0, 0, 4, 0, 0, 0, 7, 0, 0, 0, 9, 0, 0, 0]);
+    // x86 Dictionary<int, int> with one removed entry, and a bogus array length.
+    put(0x80, &[0; 0x1C]);
+    put(0x88, &0xA0u32.to_le_bytes());
+    put(0x9C, &2u32.to_le_bytes());
+    put(0xA0, &[0, 0, 0, 0, 2, 0, 0, 0]);
+    put(
+        0xA8,
+        &[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0],
+    );
+    put(0xB8, &[5, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0]);
+    put(0xD0, &[0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0x7F]);
+    // An empty x86 Dictionary<int, int> at 0xE0 has no entries array.
+
+    let read = |address: u64, size: usize| {
+        let start = address as usize;
+        memory.get(start..start + size).map(|bytes| bytes.to_vec())
+    };
+    let x86 = PointerWidth::U32;
+    assert_eq!(read_string(0x10, x86, read), Ok("hi".to_string()));
+    assert_eq!(
+        read_list(0x20, 4, x86, read),
+        Ok(vec![7, 0, 0, 0, 9, 0, 0, 0])
+    );
+    assert_eq!(
+        read_array(0x40, 4, x86, read).map(|bytes| bytes.len()),
+        Ok(16)
+    );
+    let int = ClrField {
+        size: 4,
+        reference: false,
+    };
+    assert_eq!(
+        read_dictionary(0x80, int, int, x86, read),
+        Ok(vec![(vec![3, 0, 0, 0], vec![4, 0, 0, 0])])
+    );
+    assert_eq!(read_dictionary(0xE0, int, int, x86, read), Ok(vec![]));
+    assert_eq!(
+        read_array(0xD0, 4, x86, read),
+        Err(ContainerError::TooLarge {
+            size: 0x7FFF_FFFF * 4
+        })
+    );
+}
@@ -0,0 +1,208 @@
+use super::pointer::PointerWidth;
+use super::text::{decode_lossy, TextEncoding};
+
+/// Containers claiming more than this many bytes are treated as garbage instead of read.
+const MAX_CONTAINER_SIZE: u64 = 0x100_0000;
+
+// MSVC keeps strings of up to 15 bytes in this inline buffer instead of on the heap.
+const MSVC_BUFFER_SIZE: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringLayout {
+    /// MSVC release builds: a 16 byte inline buffer or heap pointer, then size and capacity.
+    Msvc,
+    /// libstdc++ with the C++11 ABI: a data pointer, then size, then the inline buffer.
+    Libstdcxx,
+}
+
+impl StringLayout {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "msvc" => Some(StringLayout::Msvc),
+            "libstdc++" => Some(StringLayout::Libstdcxx),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerError {
+    ReadFailed {
+        address: u64,
+    },
+    /// The header holds impossible values, such as a vector whose end precedes its start.
+    Corrupt,
+    TooLarge {
+        size: u64,
+    },
+}
+
+impl std::fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerError::ReadFailed { address } => {
+                write!(f, "Failed to read memory at 0x{:X}", address)
+            }
+            ContainerError::Corrupt => write!(f, "Container header is corrupt"),
+            ContainerError::TooLarge { size } => write!(
+                f,
+                "Container claims {} bytes, more than the {} byte limit",
+                size, MAX_CONTAINER_SIZE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ContainerError {}
+
+/// Reads `size` bytes, failing with the address that could not be read.
+pub fn read_exact<F>(read: &mut F, address: u64, size: usize) -> Result<Vec<u8>, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    if size == 0 {
+        return Ok(Vec::new());
+    }
+    read(address, size).ok_or(ContainerError::ReadFailed { address })
+}
+
+/// The byte size of `count` units, refused above `MAX_CONTAINER_SIZE`.
+pub fn checked_size(count: u64, unit: usize) -> Result<usize, ContainerError> {
+    let size = count.saturating_mul(unit as u64);
+    if size > MAX_CONTAINER_SIZE {
+        return Err(ContainerError::TooLarge { size });
+    }
+    Ok(size as usize)
+}
+
+/// Reads a `std::string`, or a `std::wstring` with `TextEncoding::Utf16Le`.
+pub fn read_std_string<F>(
+    address: u64,
+    layout: StringLayout,
+    encoding: TextEncoding,
+    width: PointerWidth,
+    mut read: F,
+) -> Result<String, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let w = width.size();
+    let unit = encoding.unit_size();
+    let bytes = match layout {
+        StringLayout::Msvc => {
+            let header = read_exact(&mut read, address, MSVC_BUFFER_SIZE + 2 * w)?;
+            let length = width.decode(&header[MSVC_BUFFER_SIZE..]);
+            let capacity = width.decode(&header[MSVC_BUFFER_SIZE + w..]);
+            if length > capacity {
+                return Err(ContainerError::Corrupt);
+            }
+            let size = checked_size(length, unit)?;
+            // The buffer also holds the terminator, so it fits 15 chars or 7 wide chars.
+            if capacity < (MSVC_BUFFER_SIZE / unit) as u64 {
+                header[..size].to_vec()
+            } else {
+                read_exact(&mut read, width.decode(&header), size)?
+            }
+        }
+        StringLayout::Libstdcxx => {
+            let header = read_exact(&mut read, address, 2 * w)?;
+            let size = checked_size(width.decode(&header[w..]), unit)?;
+            read_exact(&mut read, width.decode(&header), size)?
+        }
+    };
+    Ok(decode_lossy(&bytes, encoding))
+}
+
+/// Reads the elements of a `std::vector`, which both MSVC and libstdc++ lay out as first,
+/// last and end of storage pointers.
+pub fn read_vector<F>(
+    address: u64,
+    element_size: usize,
+    width: PointerWidth,
+    mut read: F,
+) -> Result<Vec<u8>, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let w = width.size();
+    let header = read_exact(&mut read, address, 2 * w)?;
+    let first = width.decode(&header);
+    let last = width.decode(&header[w..]);
+    if last < first || (last - first).checked_rem(element_size as u64) != Some(0) {
+        return Err(ContainerError::Corrupt);
+    }
+    let size = checked_size(last - first, 1)?;
+    read_exact(&mut read, first, size)
+}
+
+/// Reads a string stored as a little-endian length of `prefix` bytes, counted in code units,
+/// followed by the data.
+pub fn read_pascal_string<F>(
+    address: u64,
+    prefix: usize,
+    encoding: TextEncoding,
+    mut read: F,
+) -> Result<String, ContainerError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let header = read_exact(&mut read, address, prefix)?;
+    let mut raw = [0; 8];
+    raw[..prefix].copy_from_slice(&header);
+    let size = checked_size(u64::from_le_bytes(raw), encoding.unit_size())?;
+    let bytes = read_exact(&mut read, address + prefix as u64, size)?;
+    Ok(decode_lossy(&bytes, encoding))
+}
+
+#[test]
+fn test_containers() {
+    let mut memory = vec![0u8; 0x200];
+    let mut put = |address: usize, bytes: &[u8]| {
+        memory[address..address + bytes.len()].copy_from_slice(bytes);
+    };
+    // MSVC x64 std::string with "short" inline, and one on the heap.
+    put(0x00, b"short\0");
+    put(0x10, &5u64.to_le_bytes());
+    put(0x18, &15u64.to_le_bytes());
+    put(0x20, &0x100u64.to_le_bytes());
+    put(0x30, &20u64.to_le_bytes());
+    put(0x38, &31u64.to_le_bytes());
+    put(0x100, b"a much longer string");
+    // libstdc++ x86 std::string pointing at the same data.
+    put(0x40, &0x100u32.to_le_bytes());
+    put(0x44, &6u32.to_le_bytes());
+    // x86 std::vector<u16> over three elements, and one with a bogus end.
+    put(0x50, &0x120u32.to_le_bytes());
+    put(0x54, &0x126u32.to_le_bytes());
+    put(0x120, &[1, 0, 2, 0, 3, 0]);
+    put(0x60, &0x120u32.to_le_bytes());
+    put(0x64, &0x110u32.to_le_bytes());
+    // Pascal string with a u16 length prefix.
+    put(0x70, &[3, 0, b'a', b'b', b'c']);
+
+    let read = |address: u64, size: usize| {
+        let start = address as usize;
+        memory.get(start..start + size).map(|bytes| bytes.to_vec())
+    };
+    let utf8 = TextEncoding::Utf8;
+    let msvc =
+        |address| read_std_string(address, StringLayout::Msvc, utf8, PointerWidth::U64, read);
+    assert_eq!(msvc(0x00), Ok("short".to_string()));
+    assert_eq!(msvc(0x20), Ok("a much longer string".to_string()));
+    assert_eq!(
+        read_std_string(0x40, StringLayout::Libstdcxx, utf8, PointerWidth::U32, read),
+        Ok("a much".to_string())
+    );
+    assert_eq!(
+        read_vector(0x50, 2, PointerWidth::U32, read),
+        Ok(vec![1, 0, 2, 0, 3, 0])
+    );
+    assert_eq!(
+        read_vector(0x60, 2, PointerWidth::U32, read),
+        Err(ContainerError::Corrupt)
+    );
+    assert_eq!(
+        read_pascal_string(0x70, 2, utf8, read),
+        Ok("abc".to_string())
+    );
+}
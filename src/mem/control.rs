@@ -0,0 +1,130 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanProgress {
+    pub bytes_scanned: u64,
+    pub regions_scanned: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanAbort {
+    Cancelled,
+    TimedOut,
+}
+
+impl std::fmt::Display for ScanAbort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanAbort::Cancelled => write!(f, "Scan cancelled"),
+            ScanAbort::TimedOut => write!(f, "Scan timed out"),
+        }
+    }
+}
+
+impl std::error::Error for ScanAbort {}
+
+/// Shared flag used to stop a scan running on another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Cancellation and timeout state checked by long running scans between regions.
+#[derive(Debug, Clone, Default)]
+pub struct ScanControl {
+    pub token: Option<CancelToken>,
+    pub deadline: Option<Instant>,
+}
+
+impl ScanControl {
+    pub fn new(token: Option<CancelToken>, timeout: Option<Duration>) -> Self {
+        Self {
+            token,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    pub fn check(&self) -> Result<(), ScanAbort> {
+        if let Some(token) = &self.token {
+            if token.is_cancelled() {
+                return Err(ScanAbort::Cancelled);
+            }
+        }
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                return Err(ScanAbort::TimedOut);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Limits how often progress is reported, keeping the latest skipped update so the final
+/// state of a scan is never lost.
+#[derive(Debug, Clone)]
+pub struct ProgressThrottle {
+    interval: Duration,
+    last_report: Option<Instant>,
+    pending: Option<ScanProgress>,
+}
+
+impl ProgressThrottle {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_report: None,
+            pending: None,
+        }
+    }
+
+    /// Returns `progress` if it should be reported now, otherwise holds it back.
+    pub fn update(&mut self, progress: ScanProgress) -> Option<ScanProgress> {
+        if let Some(last_report) = self.last_report {
+            if last_report.elapsed() < self.interval {
+                self.pending = Some(progress);
+                return None;
+            }
+        }
+        self.last_report = Some(Instant::now());
+        self.pending = None;
+        Some(progress)
+    }
+
+    /// The update held back since the last report, to be reported when the scan ends.
+    pub fn finish(&mut self) -> Option<ScanProgress> {
+        self.pending.take()
+    }
+}
+
+#[test]
+fn test_progress_throttle() {
+    let progress = |regions_scanned| ScanProgress {
+        bytes_scanned: 0,
+        regions_scanned,
+    };
+    let mut throttle = ProgressThrottle::new(Duration::from_secs(60));
+    assert!(throttle.update(progress(1)).is_some());
+    assert!(throttle.update(progress(2)).is_none());
+    assert!(throttle.update(progress(3)).is_none());
+    assert_eq!(throttle.finish().map(|p| p.regions_scanned), Some(3));
+    assert!(throttle.finish().is_none());
+
+    let mut throttle = ProgressThrottle::new(Duration::from_secs(0));
+    assert!(throttle.update(progress(1)).is_some());
+    assert!(throttle.update(progress(2)).is_some());
+    assert!(throttle.finish().is_none());
+}
@@ -0,0 +1,284 @@
+/// Resolves the parts of an address expression that depend on the target process.
+pub trait AddressContext {
+    fn module_base(&mut self, name: &str) -> Option<u64>;
+    fn read_pointer(&mut self, address: u64) -> Option<u64>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    Syntax { position: usize, message: String },
+    UnknownModule(String),
+    ReadFailed(u64),
+}
+
+impl std::fmt::Display for AddressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressError::Syntax { position, message } => {
+                write!(f, "Invalid address expression at {}: {}", position, message)
+            }
+            AddressError::UnknownModule(name) => write!(f, "Module {} not found", name),
+            AddressError::ReadFailed(address) => {
+                write!(f, "Failed to read pointer at 0x{:X}", address)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Number(u64),
+    Module(String),
+    // A module name that is also a hex number, e.g. `BEEF`.
+    Symbol(String, u64),
+    Deref(Box<Node>),
+    Negate(Box<Node>),
+    Add(Box<Node>, Box<Node>),
+    Sub(Box<Node>, Box<Node>),
+    Mul(Box<Node>, Box<Node>),
+}
+
+/// A parsed address expression in Cheat Engine notation.
+///
+/// Numbers are hexadecimal, with or without `0x`, unless prefixed with `#` for decimal. Modules
+/// are quoted (`"osu!.exe"`) or bare identifiers; a bare identifier made only of hex digits, such
+/// as `DEADBEEF`, is a number when no module has that name. `[expr]` reads a pointer at `expr`,
+/// and `expr -> +offset` reads a pointer at `expr` and adds `offset`, so `"game.exe"+10 -> +4` is
+/// `["game.exe"+0x10]+0x4`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressExpression(Node);
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, AddressError> {
+        Err(AddressError::Syntax {
+            position: self.position,
+            message: message.into(),
+        })
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.position += len;
+        &rest[..len]
+    }
+
+    // chain := sum ('->' sum)*
+    fn chain(&mut self) -> Result<Node, AddressError> {
+        let mut node = self.sum()?;
+        while self.eat("->") {
+            let offset = self.sum()?;
+            node = Node::Add(Box::new(Node::Deref(Box::new(node))), Box::new(offset));
+        }
+        Ok(node)
+    }
+
+    // sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Node, AddressError> {
+        let mut node = self.product()?;
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("->") {
+                return Ok(node);
+            } else if self.eat("+") {
+                node = Node::Add(Box::new(node), Box::new(self.product()?));
+            } else if self.eat("-") {
+                node = Node::Sub(Box::new(node), Box::new(self.product()?));
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    // product := unary ('*' unary)*
+    fn product(&mut self) -> Result<Node, AddressError> {
+        let mut node = self.unary()?;
+        while self.eat("*") {
+            node = Node::Mul(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    // unary := ('+' | '-') unary | atom
+    fn unary(&mut self) -> Result<Node, AddressError> {
+        if self.eat("+") {
+            self.unary()
+        } else if self.rest().starts_with("->") {
+            self.error("expected a value")
+        } else if self.eat("-") {
+            Ok(Node::Negate(Box::new(self.unary()?)))
+        } else {
+            self.atom()
+        }
+    }
+
+    // atom := number | module | '[' chain ']' | '(' chain ')'
+    fn atom(&mut self) -> Result<Node, AddressError> {
+        self.skip_whitespace();
+        if self.eat("[") {
+            let node = self.chain()?;
+            if !self.eat("]") {
+                return self.error("expected `]`");
+            }
+            return Ok(Node::Deref(Box::new(node)));
+        }
+        if self.eat("(") {
+            let node = self.chain()?;
+            if !self.eat(")") {
+                return self.error("expected `)`");
+            }
+            return Ok(node);
+        }
+        if self.eat("\"") {
+            let name = self.take_while(|c| c != '"');
+            if !self.eat("\"") {
+                return self.error("unterminated module name");
+            }
+            return Ok(Node::Module(name.to_string()));
+        }
+
+        let start = self.position;
+        let rest = self.rest();
+        if rest.starts_with("0x") || rest.starts_with("0X") {
+            self.position += 2;
+            let digits = self.take_while(|c| c.is_ascii_hexdigit());
+            return match u64::from_str_radix(digits, 16) {
+                Ok(value) => Ok(Node::Number(value)),
+                Err(_) => self.error("invalid hexadecimal number"),
+            };
+        }
+        if rest.starts_with('#') {
+            self.position += 1;
+            let digits = self.take_while(|c| c.is_ascii_digit());
+            return match digits.parse() {
+                Ok(value) => Ok(Node::Number(value)),
+                Err(_) => self.error("invalid decimal number"),
+            };
+        }
+        let name = self.take_while(|c| c.is_alphanumeric() || "_.!$@".contains(c));
+        if name.is_empty() {
+            self.position = start;
+            return self.error("expected a number, module name, `[` or `(`");
+        }
+        if name.chars().all(|c| c.is_ascii_hexdigit()) {
+            let value = match u64::from_str_radix(name, 16) {
+                Ok(value) => value,
+                Err(_) => {
+                    self.position = start;
+                    return self.error("invalid hexadecimal number");
+                }
+            };
+            // Numbers can't name modules; letters only might.
+            if name.starts_with(|c: char| c.is_ascii_digit()) {
+                return Ok(Node::Number(value));
+            }
+            return Ok(Node::Symbol(name.to_string(), value));
+        }
+        Ok(Node::Module(name.to_string()))
+    }
+}
+
+fn evaluate(node: &Node, context: &mut dyn AddressContext) -> Result<u64, AddressError> {
+    Ok(match node {
+        Node::Number(value) => *value,
+        Node::Module(name) => context
+            .module_base(name)
+            .ok_or_else(|| AddressError::UnknownModule(name.clone()))?,
+        Node::Symbol(name, value) => context.module_base(name).unwrap_or(*value),
+        Node::Deref(inner) => {
+            let address = evaluate(inner, context)?;
+            context
+                .read_pointer(address)
+                .ok_or(AddressError::ReadFailed(address))?
+        }
+        Node::Negate(inner) => evaluate(inner, context)?.wrapping_neg(),
+        Node::Add(lhs, rhs) => evaluate(lhs, context)?.wrapping_add(evaluate(rhs, context)?),
+        Node::Sub(lhs, rhs) => evaluate(lhs, context)?.wrapping_sub(evaluate(rhs, context)?),
+        Node::Mul(lhs, rhs) => evaluate(lhs, context)?.wrapping_mul(evaluate(rhs, context)?),
+    })
+}
+
+impl AddressExpression {
+    pub fn parse(source: &str) -> Result<Self, AddressError> {
+        let mut parser = Parser {
+            source,
+            position: 0,
+        };
+        let node = parser.chain()?;
+        parser.skip_whitespace();
+        if !parser.rest().is_empty() {
+            return parser.error("unexpected trailing characters");
+        }
+        Ok(Self(node))
+    }
+
+    pub fn evaluate(&self, context: &mut dyn AddressContext) -> Result<u64, AddressError> {
+        evaluate(&self.0, context)
+    }
+}
+
+#[test]
+fn test_evaluate() {
+    struct TestContext;
+    impl AddressContext for TestContext {
+        fn module_base(&mut self, name: &str) -> Option<u64> {
+            match name {
+                "osu!.exe" => Some(0x400000),
+                _ => None,
+            }
+        }
+
+        fn read_pointer(&mut self, address: u64) -> Option<u64> {
+            match address {
+                0x400010 => Some(0x1000),
+                0x1004 => Some(0x2000),
+                _ => None,
+            }
+        }
+    }
+
+    let eval = |source: &str| AddressExpression::parse(source)?.evaluate(&mut TestContext);
+    assert_eq!(eval("0x10 + 16 * 2"), Ok(0x3C));
+    assert_eq!(eval("#16 * 2 + BEEF"), Ok(0xBF0F));
+    assert_eq!(eval("\"osu!.exe\"+0x10"), Ok(0x400010));
+    assert_eq!(eval("\"osu!.exe\"+1A2B3C"), Ok(0x5A2B3C));
+    assert_eq!(eval("osu!.exe+10 -> +4"), Ok(0x1004));
+    assert_eq!(eval("[osu!.exe+0x10] + 4"), Ok(0x1004));
+    assert_eq!(eval("[[osu!.exe+0x10]+4]"), Ok(0x2000));
+    assert_eq!(eval("\"osu!.exe\"+0x10 -> +0x4 -> -0x10"), Ok(0x1FF0));
+    assert_eq!(
+        eval("game.exe+1"),
+        Err(AddressError::UnknownModule("game.exe".to_string()))
+    );
+    assert_eq!(eval("[0x20]"), Err(AddressError::ReadFailed(0x20)));
+    assert!(eval("osu!.exe+").is_err());
+    assert!(eval("[0x10").is_err());
+    assert!(eval("#").is_err());
+    assert!(eval("FFFFFFFFFFFFFFFFF").is_err());
+}
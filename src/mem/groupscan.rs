@@ -0,0 +1,89 @@
+use super::value::{Value, ValueType};
+use super::valuescan::{FloatOptions, ScanFilter};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroupField {
+    /// Offset from the structure base.
+    pub offset: usize,
+    pub ty: ValueType,
+    /// `ScanFilter::Unknown` accepts any value, the field then only has to be readable.
+    pub filter: ScanFilter,
+    pub float: FloatOptions,
+}
+
+/// Several typed values at fixed offsets from an unknown base, e.g. an HP float, a level
+/// int four bytes later and a pointer at +0x10.
+#[derive(Debug, Clone, Default)]
+pub struct GroupScan {
+    fields: Vec<GroupField>,
+    // Byte span covered by the fields, from the base.
+    len: usize,
+}
+
+impl GroupScan {
+    pub fn new(fields: Vec<GroupField>) -> Self {
+        let len = fields
+            .iter()
+            .map(|field| field.offset + field.ty.size())
+            .max()
+            .unwrap_or(0);
+        Self { fields, len }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn matches_at(&self, buffer: &[u8], base: usize) -> bool {
+        if buffer.len() < base + self.len {
+            return false;
+        }
+        self.fields.iter().all(|field| {
+            let value = Value::decode(field.ty, &buffer[base + field.offset..]);
+            field.filter.matches(value, value, &field.float)
+        })
+    }
+
+    /// Returns every offset into `buffer`, a multiple of `alignment`, where all fields match.
+    pub fn scan_all(&self, buffer: &[u8], alignment: usize) -> Vec<usize> {
+        if self.fields.is_empty() || buffer.len() < self.len {
+            return Vec::new();
+        }
+        (0..=buffer.len() - self.len)
+            .step_by(alignment.max(1))
+            .filter(|&base| self.matches_at(buffer, base))
+            .collect()
+    }
+}
+
+#[test]
+fn test_group_scan() {
+    let mut buffer = vec![0u8; 0x40];
+    // hp = 87.5 at +0x8, level = 12 at +0xC, a pointer at +0x18.
+    buffer[0x8..0xC].copy_from_slice(&87.5f32.to_le_bytes());
+    buffer[0xC..0x10].copy_from_slice(&12i32.to_le_bytes());
+    buffer[0x18..0x1C].copy_from_slice(&0x400000u32.to_le_bytes());
+    let float = FloatOptions::default();
+    let group = GroupScan::new(vec![
+        GroupField {
+            offset: 0,
+            ty: ValueType::F32,
+            filter: ScanFilter::Exact(Value::F32(87.5)),
+            float,
+        },
+        GroupField {
+            offset: 4,
+            ty: ValueType::I32,
+            filter: ScanFilter::Range(Value::I32(10), Value::I32(20)),
+            float,
+        },
+        GroupField {
+            offset: 0x10,
+            ty: ValueType::U32,
+            filter: ScanFilter::Exact(Value::U32(0x400000)),
+            float,
+        },
+    ]);
+    assert_eq!(group.scan_all(&buffer, 4), vec![0x8]);
+    assert!(group.scan_all(&buffer[..0x1B], 4).is_empty());
+}
@@ -0,0 +1,519 @@
+use super::pointer::PointerWidth;
+use super::text::{read_text, TextOptions};
+
+// Upper bound on list lengths, hash chain lengths and field counts, so a stale or misread
+// pointer cannot send a walk into an endless loop.
+const MAX_ITEMS: u64 = 0x10000;
+// FIELD_ATTRIBUTE_STATIC and FIELD_ATTRIBUTE_LITERAL from `MonoType::attrs`.
+const FIELD_STATIC: u16 = 0x10;
+const FIELD_LITERAL: u16 = 0x40;
+
+/// Offsets into the Mono runtime structures. They change between Unity versions, so all of
+/// them can be overridden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonoOffsets {
+    /// `MonoDomain::domain_assemblies`, a `GSList` of `MonoAssembly`.
+    pub domain_assemblies: u64,
+    /// `MonoAssembly::aname.name`.
+    pub assembly_name: u64,
+    pub assembly_image: u64,
+    /// `MonoImage::class_cache`, an inline `MonoInternalHashTable`.
+    pub image_class_cache: u64,
+    pub hash_table_size: u64,
+    pub hash_table_table: u64,
+    pub class_parent: u64,
+    pub class_name: u64,
+    pub class_namespace: u64,
+    pub class_vtable_size: u64,
+    pub class_fields: u64,
+    pub class_runtime_info: u64,
+    /// `MonoClassDef::field_count`.
+    pub class_field_count: u64,
+    /// `MonoClassDef::next_class_cache`, the hash chain link.
+    pub class_next_cache: u64,
+    /// Size of a `MonoClassField`.
+    pub field_size: u64,
+    pub field_type: u64,
+    pub field_name: u64,
+    pub field_offset: u64,
+    /// `MonoType::attrs`.
+    pub type_attrs: u64,
+    /// `MonoClassRuntimeInfo::domain_vtables`.
+    pub runtime_info_vtables: u64,
+    /// `MonoVTable::vtable`, followed by the static data pointer.
+    pub vtable_data: u64,
+}
+
+impl MonoOffsets {
+    /// `mono-2.0-bdwgc.dll` as shipped with 64-bit Unity 2019 to 2021.
+    pub fn unity_x64() -> Self {
+        Self {
+            domain_assemblies: 0xA0,
+            assembly_name: 0x10,
+            assembly_image: 0x60,
+            image_class_cache: 0x4C0,
+            hash_table_size: 0x18,
+            hash_table_table: 0x20,
+            class_parent: 0x30,
+            class_name: 0x48,
+            class_namespace: 0x50,
+            class_vtable_size: 0x5C,
+            class_fields: 0x98,
+            class_runtime_info: 0xD0,
+            class_field_count: 0x100,
+            class_next_cache: 0x108,
+            field_size: 0x20,
+            field_type: 0x0,
+            field_name: 0x8,
+            field_offset: 0x18,
+            type_attrs: 0x8,
+            runtime_info_vtables: 0x8,
+            vtable_data: 0x48,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonoError {
+    ModuleNotFound,
+    /// The default offsets only describe 64-bit `mono-2.0-bdwgc.dll`.
+    OffsetsRequired,
+    ExportNotFound,
+    /// `mono_get_root_domain` does not start with a recognised load of the root domain.
+    UnknownCode {
+        address: u64,
+    },
+    ReadFailed {
+        address: u64,
+    },
+}
+
+impl std::fmt::Display for MonoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonoError::ModuleNotFound => write!(f, "Mono runtime module not found"),
+            MonoError::OffsetsRequired => {
+                write!(
+                    f,
+                    "This Mono runtime has no default offsets, pass them explicitly"
+                )
+            }
+            MonoError::ExportNotFound => write!(f, "mono_get_root_domain is not exported"),
+            MonoError::UnknownCode { address } => write!(
+                f,
+                "Unrecognised code in mono_get_root_domain at 0x{:X}",
+                address
+            ),
+            MonoError::ReadFailed { address } => {
+                write!(f, "Failed to read memory at 0x{:X}", address)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MonoError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonoAssembly {
+    pub name: String,
+    pub image: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonoClass {
+    pub address: u64,
+    pub namespace: String,
+    pub name: String,
+    pub parent: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonoField {
+    pub name: String,
+    /// From the start of the object for instance fields, header included, or from the static
+    /// data of the class for static fields.
+    pub offset: i32,
+    pub is_static: bool,
+    /// Where a static field lives, once the class has been initialised. Constants and
+    /// thread-static fields have none.
+    pub address: Option<u64>,
+}
+
+fn read_u64<F>(read: &mut F, address: u64, size: usize) -> Option<u64>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let bytes = read(address, size)?;
+    let mut raw = [0; 8];
+    raw[..size].copy_from_slice(&bytes);
+    Some(u64::from_le_bytes(raw))
+}
+
+/// Finds an export of the PE module at `base` by name.
+pub fn find_export<F>(base: u64, name: &str, read: &mut F) -> Option<u64>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let nt = base + read_u64(read, base + 0x3C, 4)?;
+    // The export directory is the first data directory, placed after the PE32 or PE32+ fields.
+    let directory = match read_u64(read, nt + 0x18, 2)? {
+        0x20B => nt + 0x88,
+        _ => nt + 0x78,
+    };
+    let exports = base + read_u64(read, directory, 4)?;
+    let header = read(exports, 0x28)?;
+    let field = |offset: usize| {
+        u32::from_le_bytes([
+            header[offset],
+            header[offset + 1],
+            header[offset + 2],
+            header[offset + 3],
+        ]) as u64
+    };
+    let count = field(0x18).min(MAX_ITEMS);
+    let (functions, names, ordinals) = (field(0x1C), field(0x20), field(0x24));
+    let options = TextOptions {
+        max_length: 0x100,
+        ..TextOptions::default()
+    };
+    for i in 0..count {
+        let name_address = base + read_u64(read, base + names + i * 4, 4)?;
+        if read_text(name_address, &options, &mut *read).ok()? == name {
+            let ordinal = read_u64(read, base + ordinals + i * 2, 2)?;
+            return Some(base + read_u64(read, base + functions + ordinal * 4, 4)?);
+        }
+    }
+    None
+}
+
+/// Finds the root domain through the code of `mono_get_root_domain`, which only loads the
+/// `mono_root_domain` global: `mov rax, [rip + disp32]` on x64 or `mov eax, [abs32]` on x86.
+pub fn find_root_domain<F>(
+    function: u64,
+    width: PointerWidth,
+    read: &mut F,
+) -> Result<u64, MonoError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let code = read(function, 7).ok_or(MonoError::ReadFailed { address: function })?;
+    let global = match (width, &code[..]) {
+        (PointerWidth::U64, [0x48, 0x8B, 0x05, disp @ ..]) => {
+            let disp = i32::from_le_bytes([disp[0], disp[1], disp[2], disp[3]]);
+            (function + 7).wrapping_add(disp as i64 as u64)
+        }
+        (PointerWidth::U32, [0xA1, abs @ ..]) => {
+            u32::from_le_bytes([abs[0], abs[1], abs[2], abs[3]]) as u64
+        }
+        _ => return Err(MonoError::UnknownCode { address: function }),
+    };
+    read_u64(read, global, width.size()).ok_or(MonoError::ReadFailed { address: global })
+}
+
+/// A Mono root domain and the layout used to walk it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonoRuntime {
+    pub domain: u64,
+    pub width: PointerWidth,
+    pub offsets: MonoOffsets,
+}
+
+impl MonoRuntime {
+    fn pointer<F>(&self, address: u64, read: &mut F) -> Option<u64>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        read_u64(read, address, self.width.size())
+    }
+
+    fn string<F>(&self, pointer: u64, read: &mut F) -> Option<String>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        let options = TextOptions {
+            max_length: 0x400,
+            ..TextOptions::default()
+        };
+        read_text(self.pointer(pointer, read)?, &options, read).ok()
+    }
+
+    pub fn assemblies<F>(&self, read: &mut F) -> Vec<MonoAssembly>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        let mut assemblies = Vec::new();
+        let mut node = self
+            .pointer(self.domain + self.offsets.domain_assemblies, read)
+            .unwrap_or(0);
+        // GSList nodes are a data pointer followed by the next node.
+        while node != 0 && (assemblies.len() as u64) < MAX_ITEMS {
+            let assembly = match self.pointer(node, read) {
+                Some(assembly) => assembly,
+                None => break,
+            };
+            let name = self.string(assembly + self.offsets.assembly_name, read);
+            let image = self.pointer(assembly + self.offsets.assembly_image, read);
+            if let (Some(name), Some(image)) = (name, image) {
+                assemblies.push(MonoAssembly { name, image });
+            }
+            node = self
+                .pointer(node + self.width.size() as u64, read)
+                .unwrap_or(0);
+        }
+        assemblies
+    }
+
+    pub fn class<F>(&self, address: u64, read: &mut F) -> Option<MonoClass>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        Some(MonoClass {
+            address,
+            namespace: self.string(address + self.offsets.class_namespace, read)?,
+            name: self.string(address + self.offsets.class_name, read)?,
+            parent: self.pointer(address + self.offsets.class_parent, read)?,
+        })
+    }
+
+    // Calls `visit` with every class in the class cache of `image` until it returns false.
+    fn visit_classes<F, V>(&self, image: u64, read: &mut F, mut visit: V)
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+        V: FnMut(u64, &mut F) -> bool,
+    {
+        let cache = image + self.offsets.image_class_cache;
+        let size = read_u64(read, cache + self.offsets.hash_table_size, 4).unwrap_or(0);
+        let table = self
+            .pointer(cache + self.offsets.hash_table_table, read)
+            .unwrap_or(0);
+        if table == 0 || size > MAX_ITEMS * 0x10 {
+            return;
+        }
+        let w = self.width.size();
+        let buckets = match read(table, size as usize * w) {
+            Some(buckets) => buckets,
+            None => return,
+        };
+        for bucket in buckets.chunks_exact(w) {
+            let mut address = self.width.decode(bucket);
+            let mut chain = 0;
+            while address != 0 && chain < MAX_ITEMS {
+                if !visit(address, read) {
+                    return;
+                }
+                address = self
+                    .pointer(address + self.offsets.class_next_cache, read)
+                    .unwrap_or(0);
+                chain += 1;
+            }
+        }
+    }
+
+    /// Every class defined in `image`, from its class cache.
+    pub fn classes<F>(&self, image: u64, read: &mut F) -> Vec<MonoClass>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        let mut classes = Vec::new();
+        self.visit_classes(image, read, |address, read| {
+            classes.extend(self.class(address, read));
+            true
+        });
+        classes
+    }
+
+    /// Finds a class by namespace and name, searching every loaded image. Only the names of
+    /// other classes are read, and the search stops at the first match.
+    pub fn find_class<F>(&self, namespace: &str, name: &str, read: &mut F) -> Option<MonoClass>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        let mut found = None;
+        for assembly in self.assemblies(read) {
+            self.visit_classes(assembly.image, read, |address, read| {
+                let is_named = |field: u64, expected: &str, read: &mut F| {
+                    self.string(address + field, read).as_deref() == Some(expected)
+                };
+                if is_named(self.offsets.class_name, name, read)
+                    && is_named(self.offsets.class_namespace, namespace, read)
+                {
+                    found = self.class(address, read);
+                }
+                found.is_none()
+            });
+            if found.is_some() {
+                break;
+            }
+        }
+        found
+    }
+
+    // The static data of a class, which only exists once the runtime has created its vtable.
+    fn static_data<F>(&self, class: u64, read: &mut F) -> Option<u64>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        let info = self.pointer(class + self.offsets.class_runtime_info, read)?;
+        if info == 0 {
+            return None;
+        }
+        let vtable = self.pointer(info + self.offsets.runtime_info_vtables, read)?;
+        if vtable == 0 {
+            return None;
+        }
+        let vtable_size = read_u64(read, class + self.offsets.class_vtable_size, 4)?;
+        let data = self.pointer(
+            vtable + self.offsets.vtable_data + vtable_size * self.width.size() as u64,
+            read,
+        )?;
+        if data == 0 {
+            None
+        } else {
+            Some(data)
+        }
+    }
+
+    /// The fields declared by `class` itself; inherited fields are on `MonoClass::parent`.
+    pub fn fields<F>(&self, class: u64, read: &mut F) -> Vec<MonoField>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        let count = read_u64(read, class + self.offsets.class_field_count, 4).unwrap_or(0);
+        let array = self
+            .pointer(class + self.offsets.class_fields, read)
+            .unwrap_or(0);
+        if array == 0 || count == 0 || count > MAX_ITEMS {
+            return Vec::new();
+        }
+        let mut static_data = None;
+        let mut fields = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            let field = array + i * self.offsets.field_size;
+            let attrs = self
+                .pointer(field + self.offsets.field_type, read)
+                .and_then(|ty| read_u64(read, ty + self.offsets.type_attrs, 2));
+            let name = self.string(field + self.offsets.field_name, read);
+            let offset = read_u64(read, field + self.offsets.field_offset, 4);
+            let (attrs, name, offset) = match (attrs, name, offset) {
+                (Some(attrs), Some(name), Some(offset)) => {
+                    (attrs as u16, name, offset as u32 as i32)
+                }
+                _ => continue,
+            };
+            let is_static = attrs & FIELD_STATIC != 0;
+            // Thread-static fields live per thread and have an offset of -1 here.
+            let address = if is_static && attrs & FIELD_LITERAL == 0 && offset >= 0 {
+                if static_data.is_none() {
+                    static_data = Some(self.static_data(class, read));
+                }
+                static_data
+                    .flatten()
+                    .map(|data| data.wrapping_add(offset as i64 as u64))
+            } else {
+                None
+            };
+            fields.push(MonoField {
+                name,
+                offset,
+                is_static,
+                address,
+            });
+        }
+        fields
+    }
+}
+
+#[test]
+fn test_mono() {
+    let mut memory = vec![0u8; 0x1000];
+    let mut put = |address: usize, bytes: &[u8]| {
+        memory[address..address + bytes.len()].copy_from_slice(bytes);
+    };
+    let pointer = |value: u64| value.to_le_bytes();
+    // mono_get_root_domain at 0x10 loads the global at 0x40, which holds the domain 0x100.
+    put(0x10, &[0x48, 0x8B, 0x05, 0x29, 0, 0, 0, 0xC3]);
+    put(0x40, &pointer(0x100));
+    // Domain, assembly list, Assembly-CSharp and its image.
+    put(0x100 + 0xA0, &pointer(0x200));
+    put(0x200, &pointer(0x300));
+    put(0x300 + 0x10, &pointer(0x380));
+    put(0x380, b"Assembly-CSharp\0");
+    put(0x300 + 0x60, &pointer(0x400));
+    // Class cache with two buckets, one holding Game.Player.
+    put(0x400 + 0x4C0 + 0x18, &2u32.to_le_bytes());
+    put(0x400 + 0x4C0 + 0x20, &pointer(0x900));
+    put(0x908, &pointer(0xA00));
+    put(0xA00 + 0x48, &pointer(0xB40));
+    put(0xB40, b"Player\0");
+    put(0xA00 + 0x50, &pointer(0xB50));
+    put(0xB50, b"Game\0");
+    put(0xA00 + 0x5C, &2u32.to_le_bytes());
+    put(0xA00 + 0x98, &pointer(0xC00));
+    put(0xA00 + 0xD0, &pointer(0xD00));
+    put(0xA00 + 0x100, &3u32.to_le_bytes());
+    // An instance field `health` at +0x10 and a static field `Instance` at +0x8.
+    put(0xC00, &pointer(0xC80));
+    put(0xC08, &pointer(0xB60));
+    put(0xB60, b"health\0");
+    put(0xC18, &0x10u32.to_le_bytes());
+    put(0xC20, &pointer(0xC90));
+    put(0xC28, &pointer(0xB70));
+    put(0xB70, b"Instance\0");
+    put(0xC38, &0x8u32.to_le_bytes());
+    put(0xC88, &0x0006u16.to_le_bytes());
+    put(0xC98, &0x0016u16.to_le_bytes());
+    // A thread-static field `Current` at -1.
+    put(0xC40, &pointer(0xCA0));
+    put(0xC48, &pointer(0xB80));
+    put(0xB80, b"Current\0");
+    put(0xC58, &0xFFFF_FFFFu32.to_le_bytes());
+    put(0xCA8, &0x0016u16.to_le_bytes());
+    // Runtime info, vtable and the static data after its two slots.
+    put(0xD08, &pointer(0xE00));
+    put(0xE00 + 0x48 + 2 * 8, &pointer(0xF00));
+
+    let mut read = |address: u64, size: usize| {
+        let start = address as usize;
+        memory.get(start..start + size).map(|bytes| bytes.to_vec())
+    };
+    let domain = find_root_domain(0x10, PointerWidth::U64, &mut read);
+    assert_eq!(domain, Ok(0x100));
+    let runtime = MonoRuntime {
+        domain: 0x100,
+        width: PointerWidth::U64,
+        offsets: MonoOffsets::unity_x64(),
+    };
+    assert_eq!(
+        runtime.assemblies(&mut read),
+        vec![MonoAssembly {
+            name: "Assembly-CSharp".to_string(),
+            image: 0x400
+        }]
+    );
+    let class = runtime.find_class("Game", "Player", &mut read).unwrap();
+    assert_eq!(class.address, 0xA00);
+    assert_eq!(runtime.find_class("Game", "Enemy", &mut read), None);
+    assert_eq!(
+        runtime.fields(class.address, &mut read),
+        vec![
+            MonoField {
+                name: "health".to_string(),
+                offset: 0x10,
+                is_static: false,
+                address: None,
+            },
+            MonoField {
+                name: "Instance".to_string(),
+                offset: 0x8,
+                is_static: true,
+                address: Some(0xF08),
+            },
+            MonoField {
+                name: "Current".to_string(),
+                offset: -1,
+                is_static: true,
+                address: None,
+            },
+        ]
+    );
+}
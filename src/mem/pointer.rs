@@ -0,0 +1,96 @@
+use windows::Win32::Foundation::HANDLE;
+
+use super::value::ValueType;
+use super::{is_64bit_process, try_read_memory};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerWidth {
+    U32,
+    U64,
+}
+
+impl PointerWidth {
+    pub fn of_process(handle: HANDLE) -> Self {
+        if is_64bit_process(handle) {
+            PointerWidth::U64
+        } else {
+            PointerWidth::U32
+        }
+    }
+
+    pub fn from_size(size: usize) -> Option<Self> {
+        match size {
+            4 => Some(PointerWidth::U32),
+            8 => Some(PointerWidth::U64),
+            _ => None,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        match self {
+            PointerWidth::U32 => 4,
+            PointerWidth::U64 => 8,
+        }
+    }
+
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            PointerWidth::U32 => ValueType::U32,
+            PointerWidth::U64 => ValueType::U64,
+        }
+    }
+
+    /// Decodes a little-endian pointer from the start of `bytes`.
+    pub fn decode(&self, bytes: &[u8]) -> u64 {
+        let mut raw = [0; 8];
+        raw[..self.size()].copy_from_slice(&bytes[..self.size()]);
+        u64::from_le_bytes(raw)
+    }
+}
+
+/// A failed read while following a pointer chain. `hop` is the index of the read that failed:
+/// one per dereferenced pointer, then the final value read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerChainError {
+    pub hop: usize,
+    pub address: u64,
+}
+
+impl std::fmt::Display for PointerChainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Failed to read memory at 0x{:X} (hop {})",
+            self.address, self.hop
+        )
+    }
+}
+
+impl std::error::Error for PointerChainError {}
+
+pub fn read_pointer(handle: HANDLE, address: u64, width: PointerWidth) -> Option<u64> {
+    match width {
+        PointerWidth::U32 => try_read_memory::<u32>(handle, address).map(|pointer| pointer as u64),
+        PointerWidth::U64 => try_read_memory::<u64>(handle, address),
+    }
+}
+
+/// Follows `addresses`, a base address and a list of offsets, Cheat Engine style:
+/// `[[base] + offsets[0]] + offsets[1]`. The last offset is added without dereferencing.
+pub fn resolve_pointer_chain(
+    handle: HANDLE,
+    addresses: &[u64],
+    width: PointerWidth,
+) -> Result<u64, PointerChainError> {
+    let (&base, offsets) = match addresses.split_first() {
+        Some(split) => split,
+        None => return Err(PointerChainError { hop: 0, address: 0 }),
+    };
+    let mut address = base;
+    for (hop, offset) in offsets.iter().enumerate() {
+        address = read_pointer(handle, address, width)
+            .ok_or(PointerChainError { hop, address })?
+            .wrapping_add(*offset);
+    }
+    Ok(address)
+}
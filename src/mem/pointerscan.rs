@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::control::{ScanAbort, ScanControl};
+use super::pointer::PointerWidth;
+use super::Module;
+
+/// A static pointer path: `[[module + base_offset] + offsets[0]] + offsets[1]`, the same shape
+/// `resolve_pointer_chain` follows once the module base is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerPath {
+    pub module: String,
+    pub base_offset: u64,
+    pub offsets: Vec<u64>,
+}
+
+/// Prints the path as an address expression, e.g. `"osu!.exe"+0x1A2B3C -> +0x10 -> +0x44`.
+impl std::fmt::Display for PointerPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\"+0x{:X}", self.module, self.base_offset)?;
+        for offset in &self.offsets {
+            write!(f, " -> +0x{:X}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PointerScanOptions {
+    /// Maximum number of pointers dereferenced along a path.
+    pub max_depth: usize,
+    /// Maximum distance between a pointer and the address it leads to at each level.
+    pub max_offset: u64,
+    pub max_results: usize,
+    /// Maximum number of pointers followed before the search stops with the paths found so far.
+    pub max_nodes: usize,
+}
+
+impl Default for PointerScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            max_offset: 0x1000,
+            max_results: 10000,
+            max_nodes: 50_000_000,
+        }
+    }
+}
+
+/// Every pointer-sized value in readable memory that itself points into readable memory,
+/// keyed by the value so all pointers into an address range are one contiguous slice.
+#[derive(Debug, Clone, Default)]
+pub struct PointerMap {
+    // (value, address holding it), sorted once all regions are added.
+    entries: Vec<(u64, u64)>,
+}
+
+impl PointerMap {
+    /// Records every aligned pointer in `buffer`, read from `base`, for which `is_valid` holds.
+    pub fn add_region<F>(&mut self, base: u64, buffer: &[u8], width: PointerWidth, is_valid: F)
+    where
+        F: Fn(u64) -> bool,
+    {
+        let size = width.size();
+        for (i, bytes) in buffer.chunks_exact(size).enumerate() {
+            let value = match width {
+                PointerWidth::U32 => {
+                    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64
+                }
+                PointerWidth::U64 => {
+                    let mut value = [0; 8];
+                    value.copy_from_slice(bytes);
+                    u64::from_le_bytes(value)
+                }
+            };
+            if value != 0 && is_valid(value) {
+                self.entries.push((value, base + (i * size) as u64));
+            }
+        }
+    }
+
+    pub fn finish(&mut self) {
+        self.entries.sort_unstable();
+    }
+
+    fn pointers_into(&self, start: u64, end: u64) -> &[(u64, u64)] {
+        let from = self.entries.partition_point(|&(value, _)| value < start);
+        let to = self.entries.partition_point(|&(value, _)| value <= end);
+        &self.entries[from..to]
+    }
+
+    /// Walks the map backward from `target` and lists the paths that start inside a module.
+    /// A path stops at the first static address found, longer paths through it are redundant.
+    pub fn scan(
+        &self,
+        target: u64,
+        modules: &[Module],
+        options: &PointerScanOptions,
+        control: &ScanControl,
+    ) -> Result<Vec<PointerPath>, ScanAbort> {
+        let mut search = Search {
+            map: self,
+            modules,
+            options,
+            control,
+            offsets: Vec::new(),
+            results: Vec::new(),
+            dead_ends: HashMap::new(),
+            nodes: 0,
+        };
+        search.visit(target)?;
+        Ok(search.results)
+    }
+}
+
+// How many pointers are followed between cancellation checks, a power of two.
+const CHECK_INTERVAL: usize = 0x1000;
+
+struct Search<'a> {
+    map: &'a PointerMap,
+    modules: &'a [Module],
+    options: &'a PointerScanOptions,
+    control: &'a ScanControl,
+    offsets: Vec<u64>,
+    results: Vec<PointerPath>,
+    // Addresses no path reaches within the given number of further dereferences; the paths
+    // found from an address don't depend on how it was reached, only on the depth left.
+    dead_ends: HashMap<u64, usize>,
+    nodes: usize,
+}
+
+impl Search<'_> {
+    fn is_done(&self) -> bool {
+        self.results.len() >= self.options.max_results || self.nodes >= self.options.max_nodes
+    }
+
+    fn visit(&mut self, target: u64) -> Result<(), ScanAbort> {
+        let remaining = self.options.max_depth - self.offsets.len();
+        // Even a static pointer straight to the target is one dereference.
+        if remaining == 0 {
+            return Ok(());
+        }
+        if matches!(self.dead_ends.get(&target), Some(&depth) if depth >= remaining) {
+            return Ok(());
+        }
+        let found = self.results.len();
+        let start = target.saturating_sub(self.options.max_offset);
+        for &(value, address) in self.map.pointers_into(start, target) {
+            if self.is_done() {
+                return Ok(());
+            }
+            self.nodes += 1;
+            if self.nodes & (CHECK_INTERVAL - 1) == 0 {
+                self.control.check()?;
+            }
+            self.offsets.push(target - value);
+            let module = self.modules.iter().find(|module| {
+                address >= module.base_address && address - module.base_address < module.size as u64
+            });
+            match module {
+                Some(module) => self.results.push(PointerPath {
+                    module: module.name.clone(),
+                    base_offset: address - module.base_address,
+                    offsets: self.offsets.iter().rev().copied().collect(),
+                }),
+                None if remaining > 1 => self.visit(address)?,
+                None => {}
+            }
+            self.offsets.pop();
+        }
+        // A search cut short by the limits may have missed paths.
+        if self.results.len() == found && !self.is_done() {
+            self.dead_ends.insert(target, remaining);
+        }
+        Ok(())
+    }
+}
+
+/// Writes one path per line: module, base offset and comma-separated offsets, tab-separated.
+pub fn save_pointer_paths(path: &Path, paths: &[PointerPath]) -> io::Result<()> {
+    let mut contents = String::new();
+    for pointer_path in paths {
+        let offsets: Vec<String> = pointer_path
+            .offsets
+            .iter()
+            .map(|offset| format!("{:x}", offset))
+            .collect();
+        contents.push_str(&format!(
+            "{}\t{:x}\t{}\n",
+            pointer_path.module,
+            pointer_path.base_offset,
+            offsets.join(",")
+        ));
+    }
+    fs::write(path, contents)
+}
+
+pub fn load_pointer_paths(path: &Path) -> io::Result<Vec<PointerPath>> {
+    let contents = fs::read_to_string(path)?;
+    let mut paths = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid pointer path on line {}", i + 1),
+            )
+        };
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (module, base_offset, offsets) = match fields[..] {
+            [module, base_offset, offsets] => (module, base_offset, offsets),
+            _ => return Err(invalid()),
+        };
+        let base_offset = u64::from_str_radix(base_offset, 16).map_err(|_| invalid())?;
+        let offsets = offsets
+            .split(',')
+            .filter(|offset| !offset.is_empty())
+            .map(|offset| u64::from_str_radix(offset, 16))
+            .collect::<Result<Vec<u64>, _>>()
+            .map_err(|_| invalid())?;
+        paths.push(PointerPath {
+            module: module.to_string(),
+            base_offset,
+            offsets,
+        });
+    }
+    Ok(paths)
+}
+
+#[test]
+fn test_scan() {
+    let modules = [Module {
+        base_address: 0x400000,
+        size: 0x1000,
+        name: "game.exe".to_string(),
+        path: String::new(),
+    }];
+    let mut map = PointerMap::default();
+    let is_valid = |value| value >= 0x400000;
+    // game.exe+0x10 -> 0x500000, 0x500008 -> 0x600000, target is 0x600020.
+    let mut module_data = vec![0; 0x20];
+    module_data[0x10..0x14].copy_from_slice(&0x500000u32.to_le_bytes());
+    map.add_region(0x400000, &module_data, PointerWidth::U32, is_valid);
+    let mut heap = vec![0; 0x10];
+    heap[0x8..0xC].copy_from_slice(&0x600000u32.to_le_bytes());
+    map.add_region(0x500000, &heap, PointerWidth::U32, is_valid);
+    map.finish();
+
+    let options = PointerScanOptions::default();
+    let paths = map
+        .scan(0x600020, &modules, &options, &ScanControl::default())
+        .unwrap();
+    let expected = PointerPath {
+        module: "game.exe".to_string(),
+        base_offset: 0x10,
+        offsets: vec![0x8, 0x20],
+    };
+    assert_eq!(paths, vec![expected.clone()]);
+    assert_eq!(expected.to_string(), "\"game.exe\"+0x10 -> +0x8 -> +0x20");
+
+    let file = std::env::temp_dir().join("mem-tool-pointer-paths-test.txt");
+    save_pointer_paths(&file, &paths).unwrap();
+    let loaded = load_pointer_paths(&file).unwrap();
+    fs::remove_file(&file).unwrap();
+    assert_eq!(loaded, paths);
+
+    let shallow = PointerScanOptions {
+        max_depth: 1,
+        ..options
+    };
+    let paths = map
+        .scan(0x600020, &modules, &shallow, &ScanControl::default())
+        .unwrap();
+    assert!(paths.is_empty());
+
+    // A static pointer to the target still needs a depth of 1.
+    let paths = map
+        .scan(0x500008, &modules, &shallow, &ScanControl::default())
+        .unwrap();
+    assert_eq!(paths.len(), 1);
+    let none = PointerScanOptions {
+        max_depth: 0,
+        ..options
+    };
+    let paths = map
+        .scan(0x500008, &modules, &none, &ScanControl::default())
+        .unwrap();
+    assert!(paths.is_empty());
+
+    // Following the first pointer uses up the budget before the module is reached.
+    let limited = PointerScanOptions {
+        max_nodes: 1,
+        ..options
+    };
+    let paths = map
+        .scan(0x600020, &modules, &limited, &ScanControl::default())
+        .unwrap();
+    assert!(paths.is_empty());
+}
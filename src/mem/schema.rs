@@ -0,0 +1,194 @@
+use super::pointer::PointerWidth;
+use super::value::{Value, ValueType};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    Value(ValueType),
+    /// A pointer of the process width, followed into the schema while depth remains.
+    Pointer(Option<Schema>),
+    /// A structure stored inline.
+    Struct(Schema),
+    /// A fixed number of consecutive elements.
+    Array(Box<FieldType>, usize),
+}
+
+impl FieldType {
+    pub fn size(&self, width: PointerWidth) -> usize {
+        match self {
+            FieldType::Value(ty) => ty.size(),
+            FieldType::Pointer(_) => width.size(),
+            FieldType::Struct(schema) => schema.size(width),
+            FieldType::Array(element, count) => element.size(width) * count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaField {
+    pub name: String,
+    pub offset: usize,
+    pub ty: FieldType,
+}
+
+/// A struct layout, read with one bulk read of the range its fields cover plus one read per
+/// followed pointer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    pub fields: Vec<SchemaField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Value(Value),
+    /// A pointer that was not followed, either because it has no schema or depth ran out.
+    Pointer(u64),
+    /// A followed pointer that was null or could not be read.
+    Null,
+    Struct(Vec<(String, FieldValue)>),
+    Array(Vec<FieldValue>),
+}
+
+impl Schema {
+    pub fn size(&self, width: PointerWidth) -> usize {
+        self.fields
+            .iter()
+            .map(|field| field.offset + field.ty.size(width))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Decodes `buffer`, which must hold at least `self.size(width)` bytes. Pointers are followed
+    /// through `read` up to `depth` levels deep.
+    pub fn decode<F>(
+        &self,
+        buffer: &[u8],
+        width: PointerWidth,
+        depth: u32,
+        read: &mut F,
+    ) -> FieldValue
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        FieldValue::Struct(
+            self.fields
+                .iter()
+                .map(|field| {
+                    let value =
+                        decode_field(&field.ty, &buffer[field.offset..], width, depth, read);
+                    (field.name.clone(), value)
+                })
+                .collect(),
+        )
+    }
+}
+
+fn decode_field<F>(
+    ty: &FieldType,
+    bytes: &[u8],
+    width: PointerWidth,
+    depth: u32,
+    read: &mut F,
+) -> FieldValue
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    match ty {
+        FieldType::Value(ty) => FieldValue::Value(Value::decode(*ty, bytes)),
+        FieldType::Pointer(target) => {
+            let address = match Value::decode(width.value_type(), bytes) {
+                Value::U32(address) => address as u64,
+                Value::U64(address) => address,
+                _ => unreachable!(),
+            };
+            match target {
+                Some(schema) if depth > 0 => {
+                    if address == 0 {
+                        return FieldValue::Null;
+                    }
+                    match read(address, schema.size(width)) {
+                        Some(buffer) => schema.decode(&buffer, width, depth - 1, read),
+                        None => FieldValue::Null,
+                    }
+                }
+                _ => FieldValue::Pointer(address),
+            }
+        }
+        FieldType::Struct(schema) => schema.decode(bytes, width, depth, read),
+        FieldType::Array(element, count) => {
+            let size = element.size(width);
+            FieldValue::Array(
+                (0..*count)
+                    .map(|i| decode_field(element, &bytes[i * size..], width, depth, read))
+                    .collect(),
+            )
+        }
+    }
+}
+
+#[test]
+fn test_decode() {
+    let player = Schema {
+        fields: vec![
+            SchemaField {
+                name: "hp".to_string(),
+                offset: 0,
+                ty: FieldType::Value(ValueType::F32),
+            },
+            SchemaField {
+                name: "stats".to_string(),
+                offset: 4,
+                ty: FieldType::Array(Box::new(FieldType::Value(ValueType::U16)), 2),
+            },
+            SchemaField {
+                name: "target".to_string(),
+                offset: 8,
+                ty: FieldType::Pointer(Some(Schema {
+                    fields: vec![SchemaField {
+                        name: "id".to_string(),
+                        offset: 4,
+                        ty: FieldType::Value(ValueType::I32),
+                    }],
+                })),
+            },
+        ],
+    };
+    assert_eq!(player.size(PointerWidth::U32), 12);
+
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&50f32.to_le_bytes());
+    buffer.extend_from_slice(&[1, 0, 2, 0]);
+    buffer.extend_from_slice(&0x2000u32.to_le_bytes());
+    let mut reads = 0;
+    let mut read = |address: u64, size: usize| {
+        reads += 1;
+        assert_eq!((address, size), (0x2000, 8));
+        Some(vec![0, 0, 0, 0, 7, 0, 0, 0])
+    };
+
+    let target = |value| ("target".to_string(), value);
+    let fields = |target| {
+        FieldValue::Struct(vec![
+            ("hp".to_string(), FieldValue::Value(Value::F32(50.0))),
+            (
+                "stats".to_string(),
+                FieldValue::Array(vec![
+                    FieldValue::Value(Value::U16(1)),
+                    FieldValue::Value(Value::U16(2)),
+                ]),
+            ),
+            target,
+        ])
+    };
+    assert_eq!(
+        player.decode(&buffer, PointerWidth::U32, 1, &mut read),
+        fields(target(FieldValue::Struct(vec![(
+            "id".to_string(),
+            FieldValue::Value(Value::I32(7))
+        )])))
+    );
+    assert_eq!(
+        player.decode(&buffer, PointerWidth::U32, 0, &mut read),
+        fields(target(FieldValue::Pointer(0x2000)))
+    );
+    assert_eq!(reads, 1);
+}
@@ -1,23 +1,44 @@
+use std::num::ParseIntError;
+
+// Backward searches read this much at a time, nearest chunk first.
+const REVERSE_CHUNK_SIZE: u64 = 0x10000;
+
+#[derive(Debug, Clone)]
 pub struct Signature {
     pub value: Vec<u8>,
     pub mask: Vec<char>,
 }
 
 impl Signature {
-    pub fn new(signature: &str) -> Self {
+    pub fn new(signature: &str) -> Result<Self, ParseIntError> {
         let mut value: Vec<u8> = Vec::new();
         let mut mask: Vec<char> = Vec::new();
-        signature.split(" ").for_each(|x| {
+        for x in signature.split(' ') {
             let m = if x == "?" { '?' } else { 'x' };
             let v = if m == 'x' {
-                i32::from_str_radix(x, 16).unwrap() as u8
+                u8::from_str_radix(x, 16)?
             } else {
                 0
             };
             value.push(v);
             mask.push(m);
-        });
-        Self { value, mask }
+        }
+        Ok(Self { value, mask })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            value: bytes.to_vec(),
+            mask: vec!['x'; bytes.len()],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    pub fn wildcard_count(&self) -> usize {
+        self.mask.iter().filter(|&&m| m == '?').count()
     }
 
     pub fn scan(&self, buffer: &[u8]) -> Option<u32> {
@@ -42,4 +63,123 @@ impl Signature {
         }
         return None;
     }
+
+    pub fn matches_at(&self, buffer: &[u8], offset: usize) -> bool {
+        match buffer.get(offset..offset + self.value.len()) {
+            Some(window) => window
+                .iter()
+                .zip(self.value.iter().zip(&self.mask))
+                .all(|(byte, (value, mask))| *mask == '?' || value == byte),
+            None => false,
+        }
+    }
+
+    pub fn scan_all(&self, buffer: &[u8]) -> Vec<u32> {
+        (0..buffer.len())
+            .filter(|&i| self.matches_at(buffer, i))
+            .map(|i| i as u32)
+            .collect()
+    }
+
+    /// Finds the last match lying entirely inside `buffer`.
+    pub fn scan_reverse(&self, buffer: &[u8]) -> Option<u32> {
+        let last = buffer.len().checked_sub(self.value.len())?;
+        (0..=last)
+            .rev()
+            .find(|&i| self.matches_at(buffer, i))
+            .map(|i| i as u32)
+    }
+}
+
+/// Searches `[start, end)` backward one chunk at a time until `find` returns the offset of a
+/// match in a chunk, given the chunk and its address. Chunks overlap by `overlap` bytes so a
+/// match straddling a chunk boundary is still seen whole. An unreadable chunk ends the search.
+pub fn search_backward<R, F>(
+    start: u64,
+    mut end: u64,
+    overlap: usize,
+    mut read: R,
+    mut find: F,
+) -> Option<u64>
+where
+    R: FnMut(u64, usize) -> Option<Vec<u8>>,
+    F: FnMut(&[u8], u64) -> Option<usize>,
+{
+    let overlap = (overlap as u64).min(REVERSE_CHUNK_SIZE - 1);
+    while end > start {
+        let chunk_start = end.saturating_sub(REVERSE_CHUNK_SIZE).max(start);
+        let chunk = read(chunk_start, (end - chunk_start) as usize)?;
+        if let Some(offset) = find(&chunk, chunk_start) {
+            return Some(chunk_start + offset as u64);
+        }
+        if chunk_start == start {
+            break;
+        }
+        end = chunk_start + overlap;
+    }
+    None
+}
+
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (value, mask)) in self.value.iter().zip(&self.mask).enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            if *mask == '?' {
+                write!(f, "?")?;
+            } else {
+                write!(f, "{:02X}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse() {
+    let sig = Signature::new("55 8B EC ? 56").unwrap();
+    assert_eq!(sig.len(), 5);
+    assert_eq!(sig.wildcard_count(), 1);
+    assert_eq!(sig.to_string(), "55 8B EC ? 56");
+    assert!(Signature::new("55 8B ZZ").is_err());
+}
+
+#[test]
+fn test_scan() {
+    let sig = Signature::new("8B ? 56").unwrap();
+    assert_eq!(sig.scan(&[0x90, 0x8B, 0xEC, 0x56]), Some(1));
+    assert_eq!(sig.scan(&[0x90, 0x8B, 0xEC]), None);
+    assert_eq!(
+        sig.scan_reverse(&[0x8B, 0x00, 0x56, 0x8B, 0x01, 0x56, 0x8B]),
+        Some(3)
+    );
+    assert_eq!(sig.scan_reverse(&[0x8B, 0x00]), None);
+    assert_eq!(
+        sig.scan_all(&[0x8B, 0x00, 0x56, 0x8B, 0x01, 0x56]),
+        vec![0, 3]
+    );
+}
+
+#[test]
+fn test_search_backward() {
+    let mut memory = vec![0u8; 0x28000];
+    // Straddles the boundary between the last two chunks.
+    memory[0x17FFF..0x18002].copy_from_slice(&[0x8B, 0x00, 0x56]);
+    memory[0x100..0x103].copy_from_slice(&[0x8B, 0x01, 0x56]);
+    let mut reads = Vec::new();
+    let mut read = |address: u64, size: usize| {
+        reads.push((address, size));
+        let start = address as usize;
+        memory.get(start..start + size).map(|bytes| bytes.to_vec())
+    };
+    let sig = Signature::new("8B ? 56").unwrap();
+    let scan = |chunk: &[u8], _| sig.scan_reverse(chunk).map(|x| x as usize);
+    assert_eq!(
+        search_backward(0, 0x28000, 2, &mut read, scan),
+        Some(0x17FFF)
+    );
+    assert_eq!(search_backward(0, 0x17FFF, 2, &mut read, scan), Some(0x100));
+    assert_eq!(search_backward(0, 0x100, 2, &mut read, scan), None);
+    assert!(reads.iter().all(|&(_, size)| size <= 0x10000));
 }
@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::pointer::PointerWidth;
+use super::sig::Signature;
+
+/// One named signature from a database file.
+///
+/// The resolved address is `match + capture`, dereferenced `deref` times, plus `post_offset`.
+/// A `relative` capture instead holds the rel32 operand of an instruction ending at
+/// `match + relative`, such as the `? ? ? ?` in `48 8B 05 ? ? ? ?`, and resolves to the
+/// address it points at before dereferencing.
+#[derive(Debug, Clone)]
+pub struct SignatureEntry {
+    pub name: String,
+    pub pattern: Signature,
+    pub module: Option<String>,
+    pub capture: u32,
+    pub relative: Option<u32>,
+    pub deref: u32,
+    pub post_offset: i64,
+    pub version: Option<String>,
+}
+
+/// Named signatures loaded from a TOML file, one table per signature. A signature that differs
+/// between game versions is written as an array of tables, one per version:
+///
+/// ```toml
+/// [status]
+/// pattern = "48 83 F8 04 73 1E"
+///
+/// [beatmap]
+/// pattern = "48 8B 05 ? ? ? ? 48 85 C0"
+/// capture = 3
+/// relative = true
+/// deref = 1
+///
+/// [[player_base]]
+/// pattern = "8B 0D ? ? ? ? 85 C9"
+/// module = "osu!.exe"
+/// capture = 2
+/// deref = 1
+/// post_offset = 0x10
+/// version = "20220523"
+///
+/// [[player_base]]
+/// pattern = "8B 15 ? ? ? ? 85 D2"
+/// module = "osu!.exe"
+/// capture = 2
+/// deref = 1
+/// version = "20230101"
+/// ```
+///
+/// Only this subset of TOML is accepted: `[name]` and `[[name]]` headers, optionally quoted,
+/// and one `key = value` per line where values are basic strings, decimal or `0x` integers
+/// and booleans. Comments start with `#`. `relative = true` reads the capture as a rel32
+/// operand ending its instruction; `instruction_end` moves the end past immediates that follow it.
+#[derive(Debug, Clone, Default)]
+pub struct SignatureDatabase {
+    pub entries: Vec<SignatureEntry>,
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+// The fields of one table, with the line each key was set on.
+type Fields = HashMap<String, (usize, Value)>;
+
+#[derive(Debug)]
+pub struct SignatureDatabaseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for SignatureDatabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for SignatureDatabaseError {}
+
+fn error<T>(line: usize, message: impl Into<String>) -> Result<T, SignatureDatabaseError> {
+    Err(SignatureDatabaseError {
+        line,
+        message: message.into(),
+    })
+}
+
+fn parse_value(line: usize, raw: &str) -> Result<Value, SignatureDatabaseError> {
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    let trailing = chars.as_str().trim_start();
+                    if !trailing.is_empty() && !trailing.starts_with('#') {
+                        return error(line, "unexpected characters after string");
+                    }
+                    return Ok(Value::String(value));
+                }
+                '\\' => match chars.next() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    _ => return error(line, "unsupported escape sequence"),
+                },
+                c => value.push(c),
+            }
+        }
+        return error(line, "unterminated string");
+    }
+
+    let raw = raw.split('#').next().unwrap_or("").trim();
+    match raw {
+        "true" => return Ok(Value::Boolean(true)),
+        "false" => return Ok(Value::Boolean(false)),
+        _ => {}
+    }
+    let (negative, digits) = match raw.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, raw.strip_prefix('+').unwrap_or(raw)),
+    };
+    let digits = digits.replace('_', "");
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16),
+        None => digits.parse::<i64>(),
+    };
+    match value {
+        Ok(value) if negative => Ok(Value::Integer(-value)),
+        Ok(value) => Ok(Value::Integer(value)),
+        Err(_) => error(line, format!("invalid value `{}`", raw)),
+    }
+}
+
+fn build_entry(
+    line: usize,
+    name: String,
+    mut fields: Fields,
+) -> Result<SignatureEntry, SignatureDatabaseError> {
+    let mut string = |key: &str| match fields.remove(key) {
+        Some((_, Value::String(value))) => Ok(Some(value)),
+        Some((at, _)) => error(at, format!("`{}` in [{}] must be a string", key, name)),
+        None => Ok(None),
+    };
+    let pattern = match string("pattern")? {
+        Some(pattern) => pattern,
+        None => return error(line, format!("[{}] is missing `pattern`", name)),
+    };
+    let module = string("module")?;
+    let version = string("version")?;
+
+    let mut integer = |key: &str, min: i64| match fields.remove(key) {
+        Some((at, Value::Integer(value))) if value < min => error(
+            at,
+            format!("`{}` in [{}] must be at least {}", key, name, min),
+        ),
+        Some((at, Value::Integer(value))) => Ok(Some((at, value))),
+        Some((at, Value::Boolean(value))) if key == "deref" => Ok(Some((at, value as i64))),
+        Some((at, _)) => error(at, format!("`{}` in [{}] must be an integer", key, name)),
+        None => Ok(None),
+    };
+    let capture = integer("capture", 0)?.map_or(0, |(_, value)| value);
+    let deref = integer("deref", 0)?.map_or(0, |(_, value)| value);
+    let post_offset = integer("post_offset", i64::MIN)?.map_or(0, |(_, value)| value);
+    let instruction_end = integer("instruction_end", capture + 4)?;
+    let relative = match (fields.remove("relative"), instruction_end) {
+        (Some((_, Value::Boolean(true))), end) => {
+            Some(end.map_or(capture + 4, |(_, value)| value) as u32)
+        }
+        (Some((_, Value::Boolean(false))), None) | (None, None) => None,
+        (Some((_, Value::Boolean(false))), Some((at, _))) | (None, Some((at, _))) => {
+            return error(
+                at,
+                format!("`instruction_end` in [{}] needs `relative = true`", name),
+            )
+        }
+        (Some((at, _)), _) => {
+            return error(at, format!("`relative` in [{}] must be a boolean", name))
+        }
+    };
+
+    if let Some((key, (at, _))) = fields.iter().min_by_key(|(_, (at, _))| *at) {
+        return error(*at, format!("unknown key `{}` in [{}]", key, name));
+    }
+    let pattern = match Signature::new(&pattern) {
+        Ok(pattern) => pattern,
+        Err(err) => return error(line, format!("invalid pattern in [{}]: {}", name, err)),
+    };
+
+    Ok(SignatureEntry {
+        name,
+        pattern,
+        module,
+        capture: capture as u32,
+        relative,
+        deref: deref as u32,
+        post_offset,
+        version,
+    })
+}
+
+impl SignatureEntry {
+    /// Resolves the entry from the address its pattern matched at, reading memory with `read`.
+    pub fn resolve<F>(&self, found: u64, width: PointerWidth, mut read: F) -> Result<u64, String>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+    {
+        let mut address = found.wrapping_add(self.capture as u64);
+        if let Some(end) = self.relative {
+            let operand = match read(address, 4) {
+                Some(bytes) if bytes.len() >= 4 => {
+                    i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+                }
+                _ => return Err(format!("Failed to read operand at 0x{:X}", address)),
+            };
+            address = found
+                .wrapping_add(end as u64)
+                .wrapping_add(operand as i64 as u64);
+        }
+        for _ in 0..self.deref {
+            address = match read(address, width.size()) {
+                Some(bytes) if bytes.len() >= width.size() => match width.decode(&bytes) {
+                    0 => return Err("Null pointer while dereferencing".into()),
+                    pointer => pointer,
+                },
+                _ => return Err(format!("Failed to read pointer at 0x{:X}", address)),
+            };
+        }
+        Ok(address.wrapping_add(self.post_offset as u64))
+    }
+}
+
+// Entries sharing a name must have distinct versions, one of which may be absent.
+fn push_entry(
+    entries: &mut Vec<SignatureEntry>,
+    line: usize,
+    entry: SignatureEntry,
+) -> Result<(), SignatureDatabaseError> {
+    if entries
+        .iter()
+        .any(|other| other.name == entry.name && other.version == entry.version)
+    {
+        return match &entry.version {
+            Some(version) => error(
+                line,
+                format!("duplicate version {} of [[{}]]", version, entry.name),
+            ),
+            None => error(
+                line,
+                format!("more than one unversioned [[{}]]", entry.name),
+            ),
+        };
+    }
+    entries.push(entry);
+    Ok(())
+}
+
+impl SignatureDatabase {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&contents)?)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, SignatureDatabaseError> {
+        let mut entries = Vec::new();
+        // Name, starting line and fields of the table currently being read.
+        let mut table: Option<(String, usize, Fields)> = None;
+        // Every table name seen so far, and whether it was an array of tables.
+        let mut tables = HashMap::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let header = match line.strip_prefix("[[") {
+                Some(header) => Some((header, true)),
+                None => line.strip_prefix('[').map(|header| (header, false)),
+            };
+            if let Some((header, array)) = header {
+                let close = if array { "]]" } else { "]" };
+                let name = match header
+                    .split('#')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .strip_suffix(close)
+                {
+                    Some(name) => name.trim().trim_matches('"').to_string(),
+                    None => return error(number, "unterminated table header"),
+                };
+                if name.is_empty() {
+                    return error(number, "empty table name");
+                }
+                match tables.insert(name.clone(), array) {
+                    Some(true) if array => {}
+                    Some(_) => return error(number, format!("duplicate signature [{}]", name)),
+                    None => {}
+                }
+                if let Some((name, line, fields)) = table.take() {
+                    push_entry(&mut entries, line, build_entry(line, name, fields)?)?;
+                }
+                table = Some((name, number, HashMap::new()));
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return error(number, "expected `key = value`"),
+            };
+            let fields = match &mut table {
+                Some((_, _, fields)) => fields,
+                None => return error(number, "key outside of a [signature] table"),
+            };
+            if fields
+                .insert(key.to_string(), (number, parse_value(number, value)?))
+                .is_some()
+            {
+                return error(number, format!("duplicate key `{}`", key));
+            }
+        }
+
+        if let Some((name, line, fields)) = table.take() {
+            push_entry(&mut entries, line, build_entry(line, name, fields)?)?;
+        }
+        Ok(Self { entries })
+    }
+}
+
+#[test]
+fn test_parse() {
+    let db = SignatureDatabase::parse(
+        r#"
+        # osu! signatures
+        [player_base]
+        pattern = "8B 0D ? ? ? ? 85 C9"
+        module = "osu!.exe"
+        capture = 2
+        deref = true
+        post_offset = 0x10 # field inside the player object
+        version = "20220523"
+
+        [status]
+        pattern = "48 83 F8 04 73 1E"
+        post_offset = -4
+        "#,
+    )
+    .unwrap();
+    assert_eq!(db.entries.len(), 2);
+    assert_eq!(db.entries[0].name, "player_base");
+    assert_eq!(db.entries[0].module.as_deref(), Some("osu!.exe"));
+    assert_eq!(db.entries[0].capture, 2);
+    assert_eq!(db.entries[0].deref, 1);
+    assert_eq!(db.entries[0].post_offset, 0x10);
+    assert_eq!(db.entries[1].module, None);
+    assert_eq!(db.entries[1].post_offset, -4);
+
+    let db = SignatureDatabase::parse(
+        r#"
+        [[status]]
+        pattern = "55"
+        version = "1"
+        [[status]]
+        pattern = "56"
+        version = "2"
+        [[status]]
+        pattern = "57"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(db.entries.len(), 3);
+    assert_eq!(db.entries[1].version.as_deref(), Some("2"));
+    assert!(SignatureDatabase::parse("[a]\npattern = \"55\"\n[a]\npattern = \"56\"\n").is_err());
+    assert!(SignatureDatabase::parse("[a]\npattern = \"55\"\n[[a]]\npattern = \"56\"\n").is_err());
+    let err = SignatureDatabase::parse(
+        "[[a]]\npattern = \"55\"\nversion = \"1\"\n[[a]]\npattern = \"56\"\nversion = \"1\"\n",
+    )
+    .unwrap_err();
+    assert_eq!(err.line, 4);
+
+    let err = SignatureDatabase::parse("[a]\npattern = \"55\"\nfoo = 1\n").unwrap_err();
+    assert_eq!(err.line, 3);
+    let err = SignatureDatabase::parse("[a]\npattern = \"55\"\ncapture = -1\n").unwrap_err();
+    assert_eq!(err.line, 3);
+    assert!(SignatureDatabase::parse("[a]\npattern = \"ZZ\"\n").is_err());
+}
+
+#[test]
+fn test_resolve() {
+    let db = SignatureDatabase::parse(
+        r#"
+        [global]
+        pattern = "48 8B 05 ? ? ? ? 48 85 C0"
+        capture = 3
+        relative = true
+        deref = 1
+        post_offset = 8
+
+        [compare]
+        pattern = "80 3D ? ? ? ? 00"
+        capture = 2
+        relative = true
+        instruction_end = 7
+        "#,
+    )
+    .unwrap();
+    assert_eq!(db.entries[0].relative, Some(7));
+    assert_eq!(db.entries[1].relative, Some(7));
+
+    // mov rax, [rip - 0x10] at 0x1000 loads the pointer stored at 0x0FF7.
+    let memory: HashMap<u64, Vec<u8>> = vec![
+        (0x1003, (-0x10i32).to_le_bytes().to_vec()),
+        (0x0FF7, 0x2000u64.to_le_bytes().to_vec()),
+    ]
+    .into_iter()
+    .collect();
+    let read = |address: u64, _: usize| memory.get(&address).cloned();
+    assert_eq!(
+        db.entries[0].resolve(0x1000, PointerWidth::U64, read),
+        Ok(0x2008)
+    );
+    assert_eq!(
+        db.entries[1].resolve(0x1001, PointerWidth::U64, read),
+        Ok(0x0FF8)
+    );
+    assert!(db.entries[1]
+        .resolve(0x2000, PointerWidth::U64, read)
+        .is_err());
+
+    assert!(SignatureDatabase::parse("[a]\npattern = \"55\"\ninstruction_end = 5\n").is_err());
+    assert!(SignatureDatabase::parse(
+        "[a]\npattern = \"55\"\ncapture = 1\nrelative = true\ninstruction_end = 4\n"
+    )
+    .is_err());
+}
@@ -0,0 +1,320 @@
+// Strings are read in chunks of this size, never crossing a page boundary, so a short string
+// at the end of a mapped page does not fail because of the unmapped page after it.
+const CHUNK_SIZE: usize = 0x100;
+const PAGE_SIZE: u64 = 0x1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Latin1,
+    Utf16Le,
+}
+
+impl TextEncoding {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "utf8" | "utf-8" => Some(TextEncoding::Utf8),
+            "latin1" => Some(TextEncoding::Latin1),
+            "utf16le" | "utf-16le" => Some(TextEncoding::Utf16Le),
+            _ => None,
+        }
+    }
+
+    /// Bytes per code unit, which is also the size of the terminator.
+    pub fn unit_size(self) -> usize {
+        match self {
+            TextEncoding::Utf16Le => 2,
+            TextEncoding::Utf8 | TextEncoding::Latin1 => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextOptions {
+    pub encoding: TextEncoding,
+    /// Maximum length in code units; longer strings are cut off there.
+    pub max_length: usize,
+    /// Fail on invalid data instead of substituting U+FFFD.
+    pub strict: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            encoding: TextEncoding::Utf8,
+            max_length: 0x1000,
+            strict: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextWriteOptions {
+    pub encoding: TextEncoding,
+    /// Append a NUL terminator of the encoding's unit size.
+    pub terminate: bool,
+    /// Size in bytes of the destination buffer, terminator included.
+    pub capacity: Option<usize>,
+}
+
+impl Default for TextWriteOptions {
+    fn default() -> Self {
+        Self {
+            encoding: TextEncoding::Utf8,
+            terminate: true,
+            capacity: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextError {
+    ReadFailed,
+    WriteFailed,
+    /// Invalid data at this byte offset from the start of the string. When encoding, the
+    /// offset is into the UTF-8 source text.
+    Invalid {
+        offset: usize,
+    },
+    TooLong {
+        size: usize,
+        capacity: usize,
+    },
+}
+
+impl std::fmt::Display for TextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextError::ReadFailed => write!(f, "Failed to read string"),
+            TextError::WriteFailed => write!(f, "Failed to write string"),
+            TextError::Invalid { offset } => write!(f, "Invalid string data at offset {}", offset),
+            TextError::TooLong { size, capacity } => write!(
+                f,
+                "String needs {} bytes but the buffer holds {}",
+                size, capacity
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TextError {}
+
+/// Reads a NUL-terminated string through `read`. Unreadable memory after the first chunk ends
+/// the string like a terminator would.
+pub fn read_text<F>(address: u64, options: &TextOptions, mut read: F) -> Result<String, TextError>
+where
+    F: FnMut(u64, usize) -> Option<Vec<u8>>,
+{
+    let unit = options.encoding.unit_size();
+    let limit = options.max_length * unit;
+    let mut bytes = Vec::new();
+    let mut terminated = false;
+    while bytes.len() < limit {
+        let cursor = address + bytes.len() as u64;
+        let page_end = (cursor | (PAGE_SIZE - 1)) + 1;
+        let size = ((page_end - cursor) as usize)
+            .min(CHUNK_SIZE)
+            .min(limit - bytes.len());
+        let chunk = match read(cursor, size) {
+            Some(chunk) => chunk,
+            None if bytes.is_empty() => return Err(TextError::ReadFailed),
+            None => break,
+        };
+        // Units before `checked` were searched by an earlier chunk.
+        let checked = bytes.len() - bytes.len() % unit;
+        bytes.extend_from_slice(&chunk);
+        if let Some(end) = bytes[checked..]
+            .chunks_exact(unit)
+            .position(|unit| unit.iter().all(|&byte| byte == 0))
+        {
+            bytes.truncate(checked + end * unit);
+            terminated = true;
+            break;
+        }
+    }
+    bytes.truncate(bytes.len() - bytes.len() % unit);
+    decode_text(&bytes, options, !terminated)
+}
+
+// A string cut off by the length limit or unreadable memory may end inside a character, which
+// is dropped rather than reported.
+fn decode_text(bytes: &[u8], options: &TextOptions, truncated: bool) -> Result<String, TextError> {
+    match options.encoding {
+        TextEncoding::Latin1 => Ok(bytes.iter().map(|&byte| byte as char).collect()),
+        TextEncoding::Utf8 => {
+            let bytes = match std::str::from_utf8(bytes) {
+                Err(err) if truncated && err.error_len().is_none() => &bytes[..err.valid_up_to()],
+                _ => bytes,
+            };
+            match std::str::from_utf8(bytes) {
+                Ok(text) => Ok(text.to_string()),
+                Err(err) if options.strict => Err(TextError::Invalid {
+                    offset: err.valid_up_to(),
+                }),
+                Err(_) => Ok(String::from_utf8_lossy(bytes).into_owned()),
+            }
+        }
+        TextEncoding::Utf16Le => {
+            let mut units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect();
+            // A high surrogate whose pair was cut off.
+            if truncated && matches!(units.last(), Some(0xD800..=0xDBFF)) {
+                units.pop();
+            }
+            let mut text = String::with_capacity(units.len());
+            let mut offset = 0;
+            for c in std::char::decode_utf16(units) {
+                match c {
+                    Ok(c) => {
+                        text.push(c);
+                        offset += c.len_utf16() * 2;
+                    }
+                    Err(_) if options.strict => return Err(TextError::Invalid { offset }),
+                    Err(_) => {
+                        text.push(std::char::REPLACEMENT_CHARACTER);
+                        offset += 2;
+                    }
+                }
+            }
+            Ok(text)
+        }
+    }
+}
+
+/// Decodes a complete string, substituting U+FFFD for invalid data.
+pub fn decode_lossy(bytes: &[u8], encoding: TextEncoding) -> String {
+    let options = TextOptions {
+        encoding,
+        ..TextOptions::default()
+    };
+    decode_text(bytes, &options, false).unwrap_or_default()
+}
+
+/// Encodes `text` for writing, refusing to exceed `options.capacity`.
+pub fn encode_text(text: &str, options: &TextWriteOptions) -> Result<Vec<u8>, TextError> {
+    let mut bytes = match options.encoding {
+        TextEncoding::Utf8 => text.as_bytes().to_vec(),
+        TextEncoding::Latin1 => {
+            let mut bytes = Vec::with_capacity(text.len());
+            for (offset, c) in text.char_indices() {
+                if c as u32 > 0xFF {
+                    return Err(TextError::Invalid { offset });
+                }
+                bytes.push(c as u8);
+            }
+            bytes
+        }
+        TextEncoding::Utf16Le => text
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes().to_vec())
+            .collect(),
+    };
+    if options.terminate {
+        bytes.resize(bytes.len() + options.encoding.unit_size(), 0);
+    }
+    match options.capacity {
+        Some(capacity) if bytes.len() > capacity => Err(TextError::TooLong {
+            size: bytes.len(),
+            capacity,
+        }),
+        _ => Ok(bytes),
+    }
+}
+
+#[test]
+fn test_read_text() {
+    let mut memory = vec![0u8; 0x2000];
+    let utf8 = "héllo wörld".as_bytes();
+    memory[0xFFC..0xFFC + utf8.len()].copy_from_slice(utf8);
+    let utf16: Vec<u8> = "日本"
+        .encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes().to_vec())
+        .collect();
+    memory[0x1800..0x1804].copy_from_slice(&utf16);
+    memory[0x1900..0x1903].copy_from_slice(&[b'a', 0xFF, b'b']);
+
+    // Reads that cross a page boundary fail, as they would if the next page were unmapped.
+    let mut read = |address: u64, size: usize| {
+        let start = address as usize;
+        if start / 0x1000 != (start + size - 1) / 0x1000 {
+            return None;
+        }
+        memory.get(start..start + size).map(|bytes| bytes.to_vec())
+    };
+    let options = TextOptions::default();
+    assert_eq!(
+        read_text(0xFFC, &options, &mut read),
+        Ok("héllo wörld".to_string())
+    );
+
+    // "hé" cut inside the 'é' drops the partial character.
+    let short = TextOptions {
+        max_length: 2,
+        ..options
+    };
+    assert_eq!(read_text(0xFFC, &short, &mut read), Ok("h".to_string()));
+
+    let wide = TextOptions {
+        encoding: TextEncoding::Utf16Le,
+        ..options
+    };
+    assert_eq!(read_text(0x1800, &wide, &mut read), Ok("日本".to_string()));
+
+    assert_eq!(
+        read_text(0x1900, &options, &mut read),
+        Ok("a\u{FFFD}b".to_string())
+    );
+    let strict = TextOptions {
+        strict: true,
+        ..options
+    };
+    assert_eq!(
+        read_text(0x1900, &strict, &mut read),
+        Err(TextError::Invalid { offset: 1 })
+    );
+    let latin1 = TextOptions {
+        encoding: TextEncoding::Latin1,
+        ..options
+    };
+    assert_eq!(
+        read_text(0x1900, &latin1, &mut read),
+        Ok("a\u{FF}b".to_string())
+    );
+    assert_eq!(
+        read_text(0x3000, &options, &mut read),
+        Err(TextError::ReadFailed)
+    );
+}
+
+#[test]
+fn test_encode_text() {
+    let options = TextWriteOptions::default();
+    assert_eq!(encode_text("hé", &options), Ok(vec![b'h', 0xC3, 0xA9, 0]));
+    let wide = TextWriteOptions {
+        encoding: TextEncoding::Utf16Le,
+        terminate: false,
+        ..options
+    };
+    assert_eq!(encode_text("hé", &wide), Ok(vec![b'h', 0, 0xE9, 0]));
+    let bounded = TextWriteOptions {
+        capacity: Some(3),
+        ..options
+    };
+    assert_eq!(
+        encode_text("hé", &bounded),
+        Err(TextError::TooLong {
+            size: 4,
+            capacity: 3
+        })
+    );
+    let latin1 = TextWriteOptions {
+        encoding: TextEncoding::Latin1,
+        ..options
+    };
+    assert_eq!(
+        encode_text("a€", &latin1),
+        Err(TextError::Invalid { offset: 1 })
+    );
+}
@@ -0,0 +1,270 @@
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Bool,
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+}
+
+impl ValueType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bool" => Some(ValueType::Bool),
+            "i8" => Some(ValueType::I8),
+            "u8" => Some(ValueType::U8),
+            "i16" => Some(ValueType::I16),
+            "u16" => Some(ValueType::U16),
+            "i32" => Some(ValueType::I32),
+            "u32" => Some(ValueType::U32),
+            "i64" => Some(ValueType::I64),
+            "u64" => Some(ValueType::U64),
+            "f32" => Some(ValueType::F32),
+            "f64" => Some(ValueType::F64),
+            _ => None,
+        }
+    }
+
+    pub fn size(self) -> usize {
+        match self {
+            ValueType::Bool | ValueType::I8 | ValueType::U8 => 1,
+            ValueType::I16 | ValueType::U16 => 2,
+            ValueType::I32 | ValueType::U32 | ValueType::F32 => 4,
+            ValueType::I64 | ValueType::U64 | ValueType::F64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "little" => Some(Endianness::Little),
+            "big" => Some(Endianness::Big),
+            _ => None,
+        }
+    }
+}
+
+/// Values of the same type order naturally; values of different types never compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Value {
+    Bool(bool),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+}
+
+impl Value {
+    /// Decodes a little-endian value from the start of `bytes`, which must hold at least `ty.size()` bytes.
+    pub fn decode(ty: ValueType, bytes: &[u8]) -> Self {
+        let mut raw = [0; 8];
+        raw[..ty.size()].copy_from_slice(&bytes[..ty.size()]);
+        let half = [raw[0], raw[1]];
+        let word = [raw[0], raw[1], raw[2], raw[3]];
+        match ty {
+            ValueType::Bool => Value::Bool(raw[0] != 0),
+            ValueType::I8 => Value::I8(raw[0] as i8),
+            ValueType::U8 => Value::U8(raw[0]),
+            ValueType::I16 => Value::I16(i16::from_le_bytes(half)),
+            ValueType::U16 => Value::U16(u16::from_le_bytes(half)),
+            ValueType::I32 => Value::I32(i32::from_le_bytes(word)),
+            ValueType::U32 => Value::U32(u32::from_le_bytes(word)),
+            ValueType::I64 => Value::I64(i64::from_le_bytes(raw)),
+            ValueType::U64 => Value::U64(u64::from_le_bytes(raw)),
+            ValueType::F32 => Value::F32(f32::from_le_bytes(word)),
+            ValueType::F64 => Value::F64(f64::from_le_bytes(raw)),
+        }
+    }
+
+    /// Like `decode`, in the given byte order.
+    pub fn decode_endian(ty: ValueType, bytes: &[u8], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::Little => Self::decode(ty, bytes),
+            Endianness::Big => {
+                let mut raw = bytes[..ty.size()].to_vec();
+                raw.reverse();
+                Self::decode(ty, &raw)
+            }
+        }
+    }
+
+    pub fn to_bytes(self, endianness: Endianness) -> Vec<u8> {
+        let mut bytes = self.to_le_bytes();
+        if endianness == Endianness::Big {
+            bytes.reverse();
+        }
+        bytes
+    }
+
+    pub fn to_le_bytes(self) -> Vec<u8> {
+        match self {
+            Value::Bool(value) => vec![value as u8],
+            Value::I8(value) => value.to_le_bytes().to_vec(),
+            Value::U8(value) => vec![value],
+            Value::I16(value) => value.to_le_bytes().to_vec(),
+            Value::U16(value) => value.to_le_bytes().to_vec(),
+            Value::I32(value) => value.to_le_bytes().to_vec(),
+            Value::U32(value) => value.to_le_bytes().to_vec(),
+            Value::I64(value) => value.to_le_bytes().to_vec(),
+            Value::U64(value) => value.to_le_bytes().to_vec(),
+            Value::F32(value) => value.to_le_bytes().to_vec(),
+            Value::F64(value) => value.to_le_bytes().to_vec(),
+        }
+    }
+
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Value::Bool(value) => value as u8 as f64,
+            Value::I8(value) => value as f64,
+            Value::U8(value) => value as f64,
+            Value::I16(value) => value as f64,
+            Value::U16(value) => value as f64,
+            Value::I32(value) => value as f64,
+            Value::U32(value) => value as f64,
+            Value::I64(value) => value as f64,
+            Value::U64(value) => value as f64,
+            Value::F32(value) => value as f64,
+            Value::F64(value) => value,
+        }
+    }
+
+    pub fn is_float(self) -> bool {
+        matches!(self, Value::F32(_) | Value::F64(_))
+    }
+
+    /// Converts a JS number to `ty`, saturating integers the way `as` casts do.
+    pub fn from_f64(ty: ValueType, value: f64) -> Self {
+        match ty {
+            ValueType::Bool => Value::Bool(value != 0.0),
+            ValueType::I8 => Value::I8(value as i8),
+            ValueType::U8 => Value::U8(value as u8),
+            ValueType::I16 => Value::I16(value as i16),
+            ValueType::U16 => Value::U16(value as u16),
+            ValueType::I32 => Value::I32(value as i32),
+            ValueType::U32 => Value::U32(value as u32),
+            ValueType::I64 => Value::I64(value as i64),
+            ValueType::U64 => Value::U64(value as u64),
+            ValueType::F32 => Value::F32(value as f32),
+            ValueType::F64 => Value::F64(value),
+        }
+    }
+
+    /// Parses a decimal integer, such as the text of a JS BigInt. Returns `None` for float
+    /// types and for values that do not fit `ty`.
+    pub fn from_decimal(ty: ValueType, text: &str) -> Option<Self> {
+        Self::from_i128(ty, text.trim().parse().ok()?)
+    }
+
+    /// Converts a JS number without rounding or saturating: `None` unless it is an integer
+    /// below 2^53 in magnitude, which JS represents exactly, and fits `ty`.
+    pub fn from_safe_integer(ty: ValueType, value: f64) -> Option<Self> {
+        const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+        // NaN and infinities have a NaN fractional part.
+        if value.fract() != 0.0 || value.abs() > MAX_SAFE_INTEGER {
+            return None;
+        }
+        Self::from_i128(ty, value as i128)
+    }
+
+    fn from_i128(ty: ValueType, value: i128) -> Option<Self> {
+        match ty {
+            ValueType::Bool => Some(Value::Bool(value != 0)),
+            ValueType::I8 => i8::try_from(value).ok().map(Value::I8),
+            ValueType::U8 => u8::try_from(value).ok().map(Value::U8),
+            ValueType::I16 => i16::try_from(value).ok().map(Value::I16),
+            ValueType::U16 => u16::try_from(value).ok().map(Value::U16),
+            ValueType::I32 => i32::try_from(value).ok().map(Value::I32),
+            ValueType::U32 => u32::try_from(value).ok().map(Value::U32),
+            ValueType::I64 => i64::try_from(value).ok().map(Value::I64),
+            ValueType::U64 => u64::try_from(value).ok().map(Value::U64),
+            ValueType::F32 | ValueType::F64 => None,
+        }
+    }
+
+    /// Adds `delta` of the same type, wrapping integers. Mismatched types return `None`.
+    pub fn checked_offset(self, delta: Value) -> Option<Value> {
+        Some(match (self, delta) {
+            (Value::I8(a), Value::I8(b)) => Value::I8(a.wrapping_add(b)),
+            (Value::U8(a), Value::U8(b)) => Value::U8(a.wrapping_add(b)),
+            (Value::I16(a), Value::I16(b)) => Value::I16(a.wrapping_add(b)),
+            (Value::U16(a), Value::U16(b)) => Value::U16(a.wrapping_add(b)),
+            (Value::I32(a), Value::I32(b)) => Value::I32(a.wrapping_add(b)),
+            (Value::U32(a), Value::U32(b)) => Value::U32(a.wrapping_add(b)),
+            (Value::I64(a), Value::I64(b)) => Value::I64(a.wrapping_add(b)),
+            (Value::U64(a), Value::U64(b)) => Value::U64(a.wrapping_add(b)),
+            (Value::F32(a), Value::F32(b)) => Value::F32(a + b),
+            (Value::F64(a), Value::F64(b)) => Value::F64(a + b),
+            _ => return None,
+        })
+    }
+
+    /// Digits after the decimal point in the shortest text form of a float, 0 for integers.
+    pub fn decimals(self) -> i32 {
+        let text = match self {
+            Value::F32(value) => value.to_string(),
+            Value::F64(value) => value.to_string(),
+            _ => return 0,
+        };
+        text.split('.')
+            .nth(1)
+            .map_or(0, |fraction| fraction.len() as i32)
+    }
+}
+
+#[test]
+fn test_endianness() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    assert_eq!(
+        Value::decode_endian(ValueType::U32, &bytes, Endianness::Big),
+        Value::U32(0x12345678)
+    );
+    assert_eq!(
+        Value::decode_endian(ValueType::U16, &bytes, Endianness::Little),
+        Value::U16(0x3412)
+    );
+    assert_eq!(
+        Value::U16(0x1234).to_bytes(Endianness::Big),
+        vec![0x12, 0x34]
+    );
+    assert_eq!(
+        Value::from_decimal(ValueType::U64, "18446744073709551615"),
+        Some(Value::U64(u64::MAX))
+    );
+    assert_eq!(Value::from_decimal(ValueType::U64, "-1"), None);
+    assert_eq!(
+        Value::from_safe_integer(ValueType::I64, -9007199254740991.0),
+        Some(Value::I64(-9007199254740991))
+    );
+    assert_eq!(Value::from_safe_integer(ValueType::U64, -1.0), None);
+    assert_eq!(Value::from_safe_integer(ValueType::U64, 1.5), None);
+    assert_eq!(
+        Value::from_safe_integer(ValueType::U64, 9007199254740992.0),
+        None
+    );
+    assert_eq!(Value::from_safe_integer(ValueType::U64, f64::NAN), None);
+    assert_eq!(
+        Value::from_decimal(ValueType::I64, "-9007199254740993"),
+        Some(Value::I64(-9007199254740993))
+    );
+}
@@ -0,0 +1,372 @@
+use super::control::{ScanAbort, ScanControl, ScanProgress};
+use super::value::{Value, ValueType};
+
+// Candidates are re-read in spans of at most this many bytes instead of one read per address.
+const NEXT_SCAN_SPAN: u64 = 0x10000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScanFilter {
+    Exact(Value),
+    /// Inclusive on both ends.
+    Range(Value, Value),
+    Unknown,
+    Changed,
+    Unchanged,
+    Increased,
+    Decreased,
+    IncreasedBy(Value),
+    DecreasedBy(Value),
+}
+
+/// How float values are compared against an operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FloatTolerance {
+    Exact,
+    /// Within an absolute distance of the operand.
+    Epsilon(f64),
+    /// The integer part matches, `1.9` matches `1`.
+    Truncated,
+    /// Rounded to as many decimals as the operand was written with, `1.46` matches `1.5`.
+    Rounded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatOptions {
+    pub tolerance: FloatTolerance,
+    /// Drops NaN and infinite values, which are almost never real game state.
+    pub finite_only: bool,
+}
+
+impl Default for FloatOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: FloatTolerance::Exact,
+            finite_only: true,
+        }
+    }
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals);
+    (value * scale).round() / scale
+}
+
+impl ScanFilter {
+    /// Whether the filter compares against the previous scan, so it cannot start a session.
+    pub fn is_relative(&self) -> bool {
+        !matches!(
+            self,
+            ScanFilter::Exact(_) | ScanFilter::Range(_, _) | ScanFilter::Unknown
+        )
+    }
+
+    pub fn matches(&self, current: Value, previous: Value, float: &FloatOptions) -> bool {
+        if current.is_float() {
+            if float.finite_only && !current.as_f64().is_finite() {
+                return false;
+            }
+            return self.matches_float(current.as_f64(), previous.as_f64(), float.tolerance);
+        }
+        match *self {
+            ScanFilter::Exact(value) => current == value,
+            ScanFilter::Range(min, max) => current >= min && current <= max,
+            ScanFilter::Unknown => true,
+            ScanFilter::Changed => current != previous,
+            ScanFilter::Unchanged => current == previous,
+            ScanFilter::Increased => current > previous,
+            ScanFilter::Decreased => current < previous,
+            ScanFilter::IncreasedBy(delta) => previous.checked_offset(delta) == Some(current),
+            ScanFilter::DecreasedBy(delta) => current.checked_offset(delta) == Some(previous),
+        }
+    }
+
+    // Truncation and rounding apply to filters with an operand, the others only honor epsilon.
+    fn matches_float(&self, current: f64, previous: f64, tolerance: FloatTolerance) -> bool {
+        let equals = |target: f64, decimals: i32| match tolerance {
+            FloatTolerance::Exact => current == target,
+            FloatTolerance::Epsilon(epsilon) => (current - target).abs() <= epsilon,
+            FloatTolerance::Truncated => current.trunc() == target.trunc(),
+            FloatTolerance::Rounded => round_to(current, decimals) == round_to(target, decimals),
+        };
+        let epsilon = match tolerance {
+            FloatTolerance::Epsilon(epsilon) => epsilon,
+            _ => 0.0,
+        };
+        match *self {
+            ScanFilter::Exact(value) => equals(value.as_f64(), value.decimals()),
+            ScanFilter::Range(min, max) => {
+                let current = match tolerance {
+                    FloatTolerance::Truncated => current.trunc(),
+                    FloatTolerance::Rounded => {
+                        round_to(current, min.decimals().max(max.decimals()))
+                    }
+                    _ => current,
+                };
+                current >= min.as_f64() - epsilon && current <= max.as_f64() + epsilon
+            }
+            ScanFilter::Unknown => true,
+            ScanFilter::Changed => (current - previous).abs() > epsilon,
+            ScanFilter::Unchanged => (current - previous).abs() <= epsilon,
+            ScanFilter::Increased => current > previous + epsilon,
+            ScanFilter::Decreased => current < previous - epsilon,
+            ScanFilter::IncreasedBy(delta) => equals(previous + delta.as_f64(), delta.decimals()),
+            ScanFilter::DecreasedBy(delta) => equals(previous - delta.as_f64(), delta.decimals()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Candidates {
+    // An unknown initial value keeps a copy of each region; every aligned offset is a candidate.
+    Snapshot(Vec<(u64, Vec<u8>)>),
+    // Sorted addresses with their last seen values packed `ty.size()` bytes apart.
+    List {
+        addresses: Vec<u64>,
+        values: Vec<u8>,
+    },
+}
+
+/// A first scan / next scan session. Candidates live natively between scans and are only
+/// copied out to JS a page at a time.
+#[derive(Debug, Clone)]
+pub struct ValueScan {
+    ty: ValueType,
+    candidates: Candidates,
+}
+
+impl ValueScan {
+    pub fn new(ty: ValueType, filter: &ScanFilter) -> Self {
+        let candidates = match filter {
+            ScanFilter::Unknown => Candidates::Snapshot(Vec::new()),
+            _ => Candidates::List {
+                addresses: Vec::new(),
+                values: Vec::new(),
+            },
+        };
+        Self { ty, candidates }
+    }
+
+    pub fn value_type(&self) -> ValueType {
+        self.ty
+    }
+
+    /// Adds the matches in `buffer`, read from `base`, to a session that has not been narrowed yet.
+    pub fn add_region(
+        &mut self,
+        base: u64,
+        buffer: Vec<u8>,
+        filter: &ScanFilter,
+        float: &FloatOptions,
+    ) {
+        let size = self.ty.size();
+        match &mut self.candidates {
+            Candidates::Snapshot(regions) => regions.push((base, buffer)),
+            Candidates::List { addresses, values } => {
+                for (i, bytes) in buffer.chunks_exact(size).enumerate() {
+                    let value = Value::decode(self.ty, bytes);
+                    if filter.matches(value, value, float) {
+                        addresses.push(base + (i * size) as u64);
+                        values.extend_from_slice(bytes);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        let size = self.ty.size();
+        match &self.candidates {
+            Candidates::Snapshot(regions) => {
+                regions.iter().map(|(_, buffer)| buffer.len() / size).sum()
+            }
+            Candidates::List { addresses, .. } => addresses.len(),
+        }
+    }
+
+    /// Returns up to `limit` candidates starting at `offset`, with the values seen by the last scan.
+    pub fn results(&self, offset: usize, limit: usize) -> Vec<(u64, Value)> {
+        let size = self.ty.size();
+        match &self.candidates {
+            Candidates::Snapshot(regions) => regions
+                .iter()
+                .flat_map(|(base, buffer)| {
+                    buffer
+                        .chunks_exact(size)
+                        .enumerate()
+                        .map(move |(i, bytes)| (base + (i * size) as u64, bytes))
+                })
+                .skip(offset)
+                .take(limit)
+                .map(|(address, bytes)| (address, Value::decode(self.ty, bytes)))
+                .collect(),
+            Candidates::List { addresses, values } => addresses
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(limit)
+                .map(|(i, address)| (*address, Value::decode(self.ty, &values[i * size..])))
+                .collect(),
+        }
+    }
+
+    /// Re-reads every candidate through `read` and keeps those matching `filter`.
+    /// Candidates that can no longer be read are dropped.
+    pub fn next_scan<F, P>(
+        &mut self,
+        filter: &ScanFilter,
+        float: &FloatOptions,
+        control: &ScanControl,
+        mut on_progress: P,
+        mut read: F,
+    ) -> Result<(), ScanAbort>
+    where
+        F: FnMut(u64, usize) -> Option<Vec<u8>>,
+        P: FnMut(ScanProgress),
+    {
+        let ty = self.ty;
+        let size = ty.size();
+        let mut next_addresses = Vec::new();
+        let mut next_values = Vec::new();
+        let mut progress = ScanProgress::default();
+        let mut keep = |address: u64, previous: &[u8], current: &[u8]| {
+            let previous = Value::decode(ty, previous);
+            if filter.matches(Value::decode(ty, current), previous, float) {
+                next_addresses.push(address);
+                next_values.extend_from_slice(current);
+            }
+        };
+
+        match &self.candidates {
+            Candidates::Snapshot(regions) => {
+                for (base, previous) in regions {
+                    control.check()?;
+                    if let Some(current) = read(*base, previous.len()) {
+                        for (i, (previous, current)) in previous
+                            .chunks_exact(size)
+                            .zip(current.chunks_exact(size))
+                            .enumerate()
+                        {
+                            keep(base + (i * size) as u64, previous, current);
+                        }
+                    }
+                    progress.bytes_scanned += previous.len() as u64;
+                    progress.regions_scanned += 1;
+                    on_progress(progress);
+                }
+            }
+            Candidates::List { addresses, values } => {
+                let mut start = 0;
+                while start < addresses.len() {
+                    control.check()?;
+                    let span_base = addresses[start];
+                    let mut end = start + 1;
+                    while end < addresses.len()
+                        && addresses[end] + size as u64 - span_base <= NEXT_SCAN_SPAN
+                    {
+                        end += 1;
+                    }
+                    let span_size = (addresses[end - 1] - span_base) as usize + size;
+                    let span = read(span_base, span_size);
+                    for i in start..end {
+                        let previous = &values[i * size..(i + 1) * size];
+                        let offset = (addresses[i] - span_base) as usize;
+                        match &span {
+                            Some(current) => {
+                                keep(addresses[i], previous, &current[offset..offset + size])
+                            }
+                            // The span may cross a page that was freed since, retry one by one.
+                            None => {
+                                if let Some(current) = read(addresses[i], size) {
+                                    keep(addresses[i], previous, &current)
+                                }
+                            }
+                        }
+                    }
+                    progress.bytes_scanned += span_size as u64;
+                    progress.regions_scanned += 1;
+                    on_progress(progress);
+                    start = end;
+                }
+            }
+        }
+
+        self.candidates = Candidates::List {
+            addresses: next_addresses,
+            values: next_values,
+        };
+        Ok(())
+    }
+}
+
+#[test]
+fn test_value_scan() {
+    let mut memory: Vec<u8> = [10u32, 20, 10, 30]
+        .iter()
+        .flat_map(|value| value.to_le_bytes().to_vec())
+        .collect();
+    let control = ScanControl::default();
+    let float = FloatOptions::default();
+
+    let filter = ScanFilter::Exact(Value::U32(10));
+    let mut scan = ValueScan::new(ValueType::U32, &filter);
+    scan.add_region(0x1000, memory.clone(), &filter, &float);
+    assert_eq!(
+        scan.results(0, 10),
+        vec![(0x1000, Value::U32(10)), (0x1008, Value::U32(10))]
+    );
+
+    memory[8..12].copy_from_slice(&15u32.to_le_bytes());
+    let read = |address: u64, size: usize| {
+        let offset = (address - 0x1000) as usize;
+        Some(memory[offset..offset + size].to_vec())
+    };
+    let filter = ScanFilter::IncreasedBy(Value::U32(5));
+    let mut reports = Vec::new();
+    scan.next_scan(&filter, &float, &control, |p| reports.push(p), read)
+        .unwrap();
+    assert_eq!(scan.results(0, 10), vec![(0x1008, Value::U32(15))]);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].bytes_scanned, 12);
+
+    let mut scan = ValueScan::new(ValueType::U32, &ScanFilter::Unknown);
+    scan.add_region(0x1000, memory.clone(), &ScanFilter::Unknown, &float);
+    assert_eq!(scan.count(), 4);
+    assert_eq!(scan.results(3, 10), vec![(0x100C, Value::U32(30))]);
+    memory[4..8].copy_from_slice(&19u32.to_le_bytes());
+    let read = |address: u64, size: usize| {
+        let offset = (address - 0x1000) as usize;
+        Some(memory[offset..offset + size].to_vec())
+    };
+    scan.next_scan(&ScanFilter::Decreased, &float, &control, |_| {}, read)
+        .unwrap();
+    assert_eq!(scan.results(0, 10), vec![(0x1004, Value::U32(19))]);
+    assert!(ScanFilter::Changed.is_relative());
+}
+
+#[test]
+fn test_float_tolerance() {
+    let options = |tolerance| FloatOptions {
+        tolerance,
+        finite_only: true,
+    };
+    let exact = ScanFilter::Exact(Value::F32(1.5));
+    let current = Value::F32(1.46);
+    assert!(!exact.matches(current, current, &options(FloatTolerance::Exact)));
+    assert!(exact.matches(current, current, &options(FloatTolerance::Rounded)));
+    assert!(exact.matches(current, current, &options(FloatTolerance::Epsilon(0.05))));
+    assert!(!exact.matches(current, current, &options(FloatTolerance::Epsilon(0.01))));
+    assert!(exact.matches(current, current, &options(FloatTolerance::Truncated)));
+
+    let nan = Value::F64(f64::NAN);
+    assert!(!ScanFilter::Unknown.matches(nan, nan, &options(FloatTolerance::Exact)));
+    let increased = ScanFilter::IncreasedBy(Value::F64(0.1));
+    assert!(increased.matches(
+        Value::F64(0.3),
+        Value::F64(0.2),
+        &options(FloatTolerance::Rounded)
+    ));
+
+    let range = ScanFilter::Range(Value::I16(-5), Value::I16(5));
+    assert!(range.matches(Value::I16(-3), Value::I16(0), &FloatOptions::default()));
+    assert!(!range.matches(Value::I16(6), Value::I16(0), &FloatOptions::default()));
+}
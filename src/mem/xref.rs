@@ -0,0 +1,224 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Call,
+    Jump,
+    RipRelative,
+    Pointer32,
+    Pointer64,
+}
+
+impl ReferenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceKind::Call => "call",
+            ReferenceKind::Jump => "jump",
+            ReferenceKind::RipRelative => "ripRelative",
+            ReferenceKind::Pointer32 => "pointer32",
+            ReferenceKind::Pointer64 => "pointer64",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    pub address: u64,
+    pub kind: ReferenceKind,
+}
+
+fn read_i32(buffer: &[u8], offset: usize) -> Option<i32> {
+    let bytes = buffer.get(offset..offset + 4)?;
+    Some(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_u64(buffer: &[u8], offset: usize) -> Option<u64> {
+    let bytes = buffer.get(offset..offset + 8)?;
+    let mut value = [0; 8];
+    value.copy_from_slice(bytes);
+    Some(u64::from_le_bytes(value))
+}
+
+// Target of a rel32 displacement at `offset`, relative to the end of an instruction `end` bytes past `offset`.
+fn relative_target(
+    buffer: &[u8],
+    base: u64,
+    offset: usize,
+    end: usize,
+    is_64bit: bool,
+) -> Option<u64> {
+    let displacement = read_i32(buffer, offset)?;
+    let next = base.wrapping_add((offset + end) as u64);
+    let target = next.wrapping_add(displacement as i64 as u64);
+    Some(if is_64bit {
+        target
+    } else {
+        target & 0xFFFF_FFFF
+    })
+}
+
+/// Finds every reference to `target` in `buffer`, which was read from `base` in the target process.
+///
+/// Code references are `E8`/`E9` rel32 calls and jumps and, on x64, RIP-relative `lea`/`mov`
+/// and indirect `call`/`jmp` operands. Data references are absolute pointers of the process width.
+/// Decoding is not instruction aligned, so hits inside other instructions are possible.
+pub fn find_references(buffer: &[u8], base: u64, target: u64, is_64bit: bool) -> Vec<Reference> {
+    find_references_to(buffer, base, |address| address == target, is_64bit)
+        .into_iter()
+        .map(|(_, reference)| reference)
+        .collect()
+}
+
+/// Like `find_references`, but in one pass for every target accepted by `is_target`. Returns
+/// each reference with the target it refers to.
+pub fn find_references_to<F>(
+    buffer: &[u8],
+    base: u64,
+    is_target: F,
+    is_64bit: bool,
+) -> Vec<(u64, Reference)>
+where
+    F: Fn(u64) -> bool,
+{
+    let matching = |target: Option<u64>| target.filter(|&target| is_target(target));
+    let mut references = Vec::new();
+    for i in 0..buffer.len() {
+        let address = base.wrapping_add(i as u64);
+        match buffer[i] {
+            0xE8 | 0xE9 => {
+                if let Some(target) = matching(relative_target(buffer, base, i + 1, 4, is_64bit)) {
+                    let kind = if buffer[i] == 0xE8 {
+                        ReferenceKind::Call
+                    } else {
+                        ReferenceKind::Jump
+                    };
+                    references.push((target, Reference { address, kind }));
+                }
+            }
+            // lea/mov r, [rip+disp32] and mov [rip+disp32], r
+            0x8D | 0x8B | 0x89
+                if is_64bit && matches!(buffer.get(i + 1), Some(modrm) if modrm & 0xC7 == 0x05) =>
+            {
+                if let Some(target) = matching(relative_target(buffer, base, i + 2, 4, true)) {
+                    let prefixed = i > 0 && (0x40..=0x4F).contains(&buffer[i - 1]);
+                    let reference = Reference {
+                        address: if prefixed { address - 1 } else { address },
+                        kind: ReferenceKind::RipRelative,
+                    };
+                    references.push((target, reference));
+                }
+            }
+            // call/jmp [rip+disp32]
+            0xFF if is_64bit && matches!(buffer.get(i + 1), Some(0x15) | Some(0x25)) => {
+                if let Some(target) = matching(relative_target(buffer, base, i + 2, 4, true)) {
+                    let reference = Reference {
+                        address,
+                        kind: ReferenceKind::RipRelative,
+                    };
+                    references.push((target, reference));
+                }
+            }
+            _ => {}
+        }
+
+        if is_64bit {
+            if let Some(target) = matching(read_u64(buffer, i)) {
+                let reference = Reference {
+                    address,
+                    kind: ReferenceKind::Pointer64,
+                };
+                references.push((target, reference));
+            }
+        } else if let Some(target) = matching(read_i32(buffer, i).map(|value| value as u32 as u64))
+        {
+            let reference = Reference {
+                address,
+                kind: ReferenceKind::Pointer32,
+            };
+            references.push((target, reference));
+        }
+    }
+    references
+}
+
+/// Bytes the function start heuristic looks at around a candidate: two bytes of padding before
+/// it and a three byte prologue from it.
+pub const FUNCTION_START_CONTEXT: usize = 5;
+
+fn is_padding(byte: u8) -> bool {
+    byte == 0xCC || byte == 0x90
+}
+
+/// Finds the last likely function start in `buffer`, which was read from `base`, with all three
+/// bytes from it inside `buffer`. A start is a 16-byte aligned address after at least two bytes
+/// of `CC` or `90` padding or, in 32-bit code only, a `55 8B EC` frame prologue. 64-bit code
+/// has no common prologue, so only padding is recognized there.
+pub fn last_function_start(buffer: &[u8], base: u64, is_64bit: bool) -> Option<usize> {
+    let last = buffer.len().checked_sub(3)?;
+    (0..=last).rev().find(|&i| {
+        let prologue = !is_64bit && buffer[i..i + 3] == [0x55, 0x8B, 0xEC];
+        let padded = i >= 2
+            && base.wrapping_add(i as u64) & 0xF == 0
+            && is_padding(buffer[i - 2])
+            && is_padding(buffer[i - 1])
+            && !is_padding(buffer[i]);
+        prologue || padded
+    })
+}
+
+#[test]
+fn test_find_references() {
+    let base = 0x401000;
+    // call 0x401010; jmp 0x401010; dd 0x401010
+    let buffer = [
+        0xE8, 0x0B, 0x00, 0x00, 0x00, 0xE9, 0x06, 0x00, 0x00, 0x00, 0x10, 0x10, 0x40, 0x00,
+    ];
+    let references = find_references(&buffer, base, 0x401010, false);
+    assert_eq!(
+        references,
+        vec![
+            Reference {
+                address: 0x401000,
+                kind: ReferenceKind::Call
+            },
+            Reference {
+                address: 0x401005,
+                kind: ReferenceKind::Jump
+            },
+            Reference {
+                address: 0x40100A,
+                kind: ReferenceKind::Pointer32
+            },
+        ]
+    );
+
+    // lea rcx, [rip+0x10]
+    let buffer = [0x48, 0x8D, 0x0D, 0x10, 0x00, 0x00, 0x00];
+    let references = find_references(&buffer, 0x140001000, 0x140001017, true);
+    assert_eq!(
+        references,
+        vec![Reference {
+            address: 0x140001000,
+            kind: ReferenceKind::RipRelative
+        }]
+    );
+
+    // call 0x401010; call 0x401020
+    let buffer = [0xE8, 0x0B, 0x00, 0x00, 0x00, 0xE8, 0x16, 0x00, 0x00, 0x00];
+    let targets: Vec<u64> =
+        find_references_to(&buffer, base, |target| target >> 8 == 0x4010, false)
+            .into_iter()
+            .map(|(target, _)| target)
+            .collect();
+    assert_eq!(targets, vec![0x401010, 0x401020]);
+}
+
+#[test]
+fn test_last_function_start() {
+    let mut buffer = vec![0x8B; 0x40];
+    // A lone CC inside an instruction before an aligned address is not padding.
+    buffer[0x0F] = 0xCC;
+    buffer[0x1E..0x20].copy_from_slice(&[0xCC, 0xCC]);
+    buffer[0x28..0x2B].copy_from_slice(&[0x55, 0x8B, 0xEC]);
+    assert_eq!(last_function_start(&buffer, 0x401000, false), Some(0x28));
+    assert_eq!(last_function_start(&buffer, 0x401000, true), Some(0x20));
+    assert_eq!(last_function_start(&buffer[..0x20], 0x401000, true), None);
+}
@@ -14,7 +14,7 @@ const main = async () => {
     // process.writeMemoryFloat64(0x0129d918, 100.00001)
     // console.log(process.readMemoryFloat64(0x0129d918))
     // console.log(Buffer.from(Buffer.from([0xc8, 0x36, 0x03, 0x04]).readInt32LE(0)))
-    // let data = process.readMemoryBufferFromPointers([address + 44], 4)
+    // let data = process.readMemoryBufferFromPointer([address + 44, 0], 4)
     // console.log(data)
     // console.log('test')
 }